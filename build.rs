@@ -77,10 +77,18 @@ fn prep_dict(out_dir: &Path, sub_path: &str, read_from: &str) {
     write_words(out_dir.join(sub_path), all_words.as_slice()).expect("Writing file should be ok");
 }
 
+fn prep_opening_book(out_dir: &Path, sub_path: &str, read_from: &str) {
+    println!("cargo:rerun-if-changed={}", read_from);
+    // The book is in the order it is played, so is kept as it is.
+    let book = read_words(Path::new(read_from)).expect("Reading file should be ok");
+    std::fs::write(out_dir.join(sub_path), book.concat()).expect("Writing file should be ok");
+}
+
 fn main() {
     let out_dir = &env::var_os("OUT_DIR").unwrap();
     let out_dir = Path::new(out_dir);
     prep_dict(out_dir, "wordles.bin", "data/wordles.txt");
     prep_dict(out_dir, "other_words.bin", "data/other_words.txt");
+    prep_opening_book(out_dir, "opening_book.bin", "data/opening_book.txt");
     println!("cargo:rerun-if-changed=build.rs");
 }
\ No newline at end of file
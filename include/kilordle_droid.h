@@ -0,0 +1,91 @@
+/* Generated by cbindgen from src/ffi.rs; regenerate with `cbindgen --output include/kilordle_droid.h`. */
+
+#ifndef KILORDLE_DROID_H
+#define KILORDLE_DROID_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/* The number of letters in a word. */
+#define KILORDLE_WORD_LENGTH 5
+
+/**
+ * The outcome of a call.
+ */
+typedef enum KilordleStatus {
+  KILORDLE_STATUS_OK = 0,
+  KILORDLE_STATUS_NULL_POINTER,
+  KILORDLE_STATUS_INVALID_WORD,
+  KILORDLE_STATUS_INVALID_RESULT,
+  KILORDLE_STATUS_HISTORY_LENGTH_MISMATCH,
+  KILORDLE_STATUS_INSUFFICIENT_REMAINING_WORDS,
+  KILORDLE_STATUS_NO_POSSIBLE_GUESSES,
+  KILORDLE_STATUS_INTERNAL_ERROR,
+} KilordleStatus;
+
+/**
+ * The state of a game being solved. Only used through pointers.
+ */
+typedef struct KilordleSolver KilordleSolver;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a solver for a new game, which must be freed with
+ * `kilordle_solver_free`.
+ */
+struct KilordleSolver *kilordle_solver_new(void);
+
+/**
+ * Frees a solver. Does nothing if `solver` is null.
+ *
+ * # Safety
+ * `solver` must be null or come from `kilordle_solver_new` and not have been
+ * freed already.
+ */
+void kilordle_solver_free(struct KilordleSolver *solver);
+
+/**
+ * Records that `guess` was entered, and what is on screen afterwards.
+ *
+ * `result_histories` holds `n_boards` strings, one for each board on screen,
+ * with five characters for each guess entered so far including this one:
+ * ' ' for no match, 'o' for a partial match, 'O' for an exact match and '?'
+ * for a tile whose colour was not read.
+ * The solver is left unchanged if anything is invalid.
+ *
+ * # Safety
+ * `solver` must be a valid solver, `guess` a NUL-terminated string, and
+ * `result_histories` must point to `n_boards` NUL-terminated strings.
+ */
+enum KilordleStatus kilordle_solver_add_turn(struct KilordleSolver *solver,
+                                             const char *guess,
+                                             const char *const *result_histories,
+                                             size_t n_boards,
+                                             size_t n_remaining_words);
+
+/**
+ * Finds the next guess, and writes it to `guess_out` as a NUL-terminated
+ * string of `KILORDLE_WORD_LENGTH` letters.
+ *
+ * # Safety
+ * `solver` must be a valid solver and `guess_out` must have room for
+ * `KILORDLE_WORD_LENGTH + 1` bytes.
+ */
+enum KilordleStatus kilordle_solver_suggest(const struct KilordleSolver *solver, char *guess_out);
+
+/**
+ * A description of `status`, as a static NUL-terminated string.
+ */
+const char *kilordle_status_message(enum KilordleStatus status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KILORDLE_DROID_H */
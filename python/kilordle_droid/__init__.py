@@ -1,8 +1,67 @@
-from typing import List
+import array
+from typing import Any, Dict, List, Optional, Sequence, Tuple
 
 from . import kilordle_droid as _kilordle_droid_rs
 from . import driver
 
+SolveHandle = _kilordle_droid_rs.SolveHandle
+Word = _kilordle_droid_rs.Word
+LetterMatch = _kilordle_droid_rs.LetterMatch
+GuessResult = _kilordle_droid_rs.GuessResult
 
-def pick_next_guess(guess_history: List[str], result_histories: List[List[str]], n_remaining_words: int) -> str:
-	return _kilordle_droid_rs.pick_next_guess(guess_history, result_histories, n_remaining_words)
\ No newline at end of file
+
+def pick_next_guess(guess_history: List[str], result_histories: List[List[str]], n_remaining_words: int,
+                    lookahead: Optional[int] = None, deadline: Optional[float] = None,
+                    threads: Optional[int] = None, solved_words: Sequence[str] = ()) -> str:
+	return _kilordle_droid_rs.pick_next_guess(guess_history, result_histories, n_remaining_words, lookahead=lookahead, deadline=deadline, threads=threads, solved_words=list(solved_words))
+
+
+def pick_next_guesses(guess_history: List[str], result_histories: List[List[str]], n_remaining_words: int,
+                      n_guesses: int, threads: Optional[int] = None, solved_words: Sequence[str] = ()) -> List[str]:
+	return _kilordle_droid_rs.pick_next_guesses(guess_history, result_histories, n_remaining_words, n_guesses, threads=threads, solved_words=list(solved_words))
+
+
+def start_solve(guess_history: List[str], result_histories: List[List[str]], n_remaining_words: int,
+                lookahead: Optional[int] = None, deadline: Optional[float] = None,
+                threads: Optional[int] = None, solved_words: Sequence[str] = ()) -> SolveHandle:
+	return _kilordle_droid_rs.start_solve(guess_history, result_histories, n_remaining_words, lookahead=lookahead, deadline=deadline, threads=threads, solved_words=list(solved_words))
+
+
+def possible_words(guess_history: List[str], result_history: List[str], solved_words: Sequence[str] = ()) -> List[str]:
+	return _kilordle_droid_rs.possible_words(guess_history, result_history, solved_words=list(solved_words))
+
+
+def count_possible_words(guess_history: List[str], result_history: List[str], solved_words: Sequence[str] = ()) -> int:
+	return _kilordle_droid_rs.count_possible_words(guess_history, result_history, solved_words=list(solved_words))
+
+
+def partition(guess: str, candidates: Sequence[str]) -> List[Tuple[str, List[str], int]]:
+	return _kilordle_droid_rs.partition(guess, list(candidates))
+
+
+def score_all_guesses(guess_history: List[str], result_histories: List[List[str]], n_remaining_words: int,
+                      threads: Optional[int] = None, solved_words: Sequence[str] = ()) -> array.array:
+	return _kilordle_droid_rs.score_all_guesses(guess_history, result_histories, n_remaining_words, threads=threads, solved_words=list(solved_words))
+
+
+def guess_pool() -> List[str]:
+	return _kilordle_droid_rs.guess_pool()
+
+
+def replay(guess_history: List[str], result_histories: List[List[List[str]]], n_remaining_words: List[int],
+           threads: Optional[int] = None) -> List[Dict[str, Any]]:
+	return _kilordle_droid_rs.replay(guess_history, result_histories, n_remaining_words, threads=threads)
+
+
+def append_game_log(path: str, guess: str, result_histories: List[List[str]], n_remaining_words: int,
+                    solver_guess: Optional[str] = None, solve_seconds: Optional[float] = None,
+                    timestamp: Optional[float] = None) -> None:
+	_kilordle_droid_rs.append_game_log(path, guess, result_histories, n_remaining_words, solver_guess=solver_guess, solve_seconds=solve_seconds, timestamp=timestamp)
+
+
+def read_game_log(path: str) -> List[Dict[str, Any]]:
+	return _kilordle_droid_rs.read_game_log(path)
+
+
+def game_state_from_columns(columns: Sequence[Dict[str, List[str]]]) -> Tuple[List[str], List[List[str]]]:
+	return _kilordle_droid_rs.game_state_from_columns(list(columns))
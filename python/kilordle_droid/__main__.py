@@ -7,6 +7,7 @@ webdriver.get('https://jonesnxt.github.io/kilordle/')
 
 controller = kilordle_droid.driver.KilordleController(webdriver)
 
+game_log_path = 'game-{}.jsonl'.format(time.strftime('%Y%m%d-%H%M%S'))
 last_enter = time.time()
 time_between_guesses = 0.25
 i = 0
@@ -18,18 +19,33 @@ while True:
     time_before_read = time.time()
     guess_history, result_histories = controller.read_result_history()
     time_before_find_guess = time.time()
-    next_guess = kilordle_droid.pick_next_guess(guess_history, result_histories, controller.remaining_words())
+    next_enter = last_enter + time_between_guesses
+    deadline = max(next_enter, time_before_find_guess + time_between_guesses / 2)
+    solve = kilordle_droid.start_solve(guess_history, result_histories, controller.remaining_words(), deadline=deadline)
+    screen_changed = False
+    while not solve.done():
+        time.sleep(0.01)
+        if controller.read_result_history() != (guess_history, result_histories):
+            solve.cancel()
+            screen_changed = True
+            break
+    if screen_changed:
+        print("Screen changed while finding a guess, starting again")
+        continue
+    next_guess = solve.result()
     time_after_find_guess = time.time()
     print("Found guess {} in {:.2f}s (took {:.2f}s to read screen)".format(next_guess, time_after_find_guess - time_before_find_guess, time_before_find_guess - time_before_read))
-    next_enter = last_enter + time_between_guesses
     if time_after_find_guess < next_enter:
         last_enter = next_enter
         time.sleep(next_enter - time_after_find_guess)
     else:
         last_enter = time_after_find_guess
     controller.enter_guess(next_guess)
+    kilordle_droid.append_game_log(game_log_path, next_guess, result_histories, words_remaining, solver_guess=next_guess,
+                                   solve_seconds=time_after_find_guess - time_before_find_guess, timestamp=last_enter)
     i += 1
 
+print("Game log written to {}".format(game_log_path))
 input('Waiting before closing...')
 webdriver.quit()
 exit(0)
@@ -11,7 +11,7 @@ from selenium.webdriver.support import expected_conditions as EC
 from selenium.webdriver.support.color import Color
 from selenium.webdriver.support.wait import WebDriverWait
 
-from ..util import is_five_lowercase_letters
+from ..kilordle_droid import Word, game_state_from_columns
 
 DriverOrElement = Union[WebDriver, WebElement]
 
@@ -87,10 +87,8 @@ class KilordleController:
         self._results_elem = _following_sibling(self._navbar_elem)
         self._remaining_rex = re.compile(r'Remaining: (\d*)/1000')
 
-    def enter_guess(self, guess: str):
-        guess = guess.lower()
-        if not is_five_lowercase_letters(guess):
-            raise ValueError("Guess must be exactly 5 letters")
+    def enter_guess(self, guess: Union[str, Word]):
+        guess = str(Word.parse(str(guess)))
         self._body_elem.send_keys(guess)
         time.sleep(0.1)
         self._body_elem.send_keys(Keys.ENTER)
@@ -100,10 +98,4 @@ class KilordleController:
 
     def read_result_history(self) -> Tuple[List[str], List[List[str]]]:
         by_column = _read_results_div(self._webdriver, self._results_elem)
-        if len(by_column) == 0:
-            return [], []
-        guess_history = by_column[0]['guessHistory']
-        if any(it['guessHistory'] != guess_history for it in by_column):
-            raise RuntimeError("Mismatch in guess histories")
-        result_histories = [it['resultHistory'] for it in by_column]
-        return guess_history, result_histories
+        return game_state_from_columns(by_column)
@@ -0,0 +1,121 @@
+//! Searches for a guess on a background thread, which Python can wait for or
+//! cancel when the screen changes under it.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use pyo3::exceptions::PyTimeoutError;
+use pyo3::prelude::*;
+use crate::python::{deadline_from_timestamp, parallelism_for_py, seconds_to_duration};
+use crate::{solve, GuessResult, PickGuessError, SearchLimits, Word};
+
+#[derive(Default)]
+struct Outcome {
+    result: Mutex<Option<Result<Word, PickGuessError>>>,
+    finished: Condvar,
+}
+
+/// A search for a guess running in the background, started by `start_solve`.
+#[pyclass]
+pub struct SolveHandle {
+    outcome: Arc<Outcome>,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl SolveHandle {
+    /// Whether the search has finished, including by being cancelled.
+    fn done(&self) -> bool {
+        self.outcome.result.lock().expect("Lock should not be poisoned").is_some()
+    }
+
+    /// Waits up to `timeout` seconds (or as long as it takes, if `None`) for
+    /// the guess, raising `TimeoutError` if the search has not finished by then.
+    #[args(timeout = "None")]
+    fn result(&self, py: Python, timeout: Option<f64>) -> PyResult<String> {
+        let timeout = timeout.map(|timeout| seconds_to_duration(timeout, "timeout")).transpose()?;
+        let outcome = &self.outcome;
+        let result = py.allow_threads(|| {
+            let result = outcome.result.lock().expect("Lock should not be poisoned");
+            let result = match timeout {
+                None => outcome.finished.wait_while(result, |result| result.is_none())
+                    .expect("Lock should not be poisoned"),
+                Some(timeout) => outcome.finished.wait_timeout_while(result, timeout, |result| result.is_none())
+                    .expect("Lock should not be poisoned").0,
+            };
+            *result
+        });
+        match result {
+            None => Err(PyTimeoutError::new_err("Search for a guess has not finished")),
+            Some(result) => Ok(result?.to_string()),
+        }
+    }
+
+    /// Stops the search as soon as possible. Unless it had already finished,
+    /// `result` then raises an error.
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed)
+    }
+}
+
+/// Python drops a handle when it is done with it, usually because the screen
+/// changed and a new search was started, so the search is stopped as well.
+impl Drop for SolveHandle {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed)
+    }
+}
+
+/// Starts finding a next guess on a background thread, taking the same
+/// arguments as `pick_next_guess`.
+#[pyfunction(lookahead = "None", deadline = "None", threads = "None", solved_words = "Vec::new()")]
+pub fn start_solve(guess_history: Vec<Word>, result_histories: Vec<Vec<GuessResult>>, n_remaining_words: usize, lookahead: Option<usize>, deadline: Option<f64>, threads: Option<usize>, solved_words: Vec<Word>) -> PyResult<SolveHandle> {
+    let parallelism = parallelism_for_py(threads)?;
+    let outcome = Arc::new(Outcome::default());
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let limits = SearchLimits { deadline: deadline.map(deadline_from_timestamp).transpose()?, cancelled: Some(cancelled.clone()) };
+
+    let worker_outcome = outcome.clone();
+    std::thread::spawn(move || {
+        let result = parallelism.install(|| {
+            solve(guess_history.as_slice(), result_histories.as_slice(), n_remaining_words, solved_words.as_slice(), lookahead, &limits)
+        });
+        *worker_outcome.result.lock().expect("Lock should not be poisoned") = Some(result);
+        worker_outcome.finished.notify_all();
+    });
+
+    Ok(SolveHandle { outcome, cancelled })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::exceptions::{PyRuntimeError, PyValueError};
+    use crate::pick_next_guess_inner;
+
+    #[test]
+    fn test_result_and_cancel() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let handle = start_solve(vec![], vec![], 1000, None, None, Some(1), vec![]).unwrap();
+            assert_eq!(handle.result(py, None).unwrap(), pick_next_guess_inner(&[], &[], 1000).unwrap().to_string());
+            assert!(handle.done());
+
+            // Looking ahead from the start of a game takes far longer than this test.
+            let handle = start_solve(vec![], vec![], 1000, Some(8), None, Some(1), vec![]).unwrap();
+            assert!(handle.result(py, Some(0.0)).unwrap_err().is_instance_of::<PyTimeoutError>(py));
+            assert!(handle.result(py, Some(f64::NAN)).unwrap_err().is_instance_of::<PyValueError>(py));
+            handle.cancel();
+            assert!(handle.result(py, None).unwrap_err().is_instance_of::<PyRuntimeError>(py));
+            assert!(handle.done());
+        });
+    }
+
+    #[test]
+    fn test_drop_cancels() {
+        let handle = start_solve(vec![], vec![], 1000, Some(8), None, Some(1), vec![]).unwrap();
+        let outcome = handle.outcome.clone();
+        drop(handle);
+        let result = outcome.finished.wait_while(outcome.result.lock().unwrap(), |result| result.is_none()).unwrap();
+        assert_eq!(*result, Some(Err(PickGuessError::Cancelled)));
+    }
+}
@@ -0,0 +1,26 @@
+//! Works out the opening book and writes it out to be embedded in the solver.
+
+use std::io::Write;
+use kilordle_droid::opening_book::generate;
+
+const USAGE: &str = "Usage: opening_book [N_GUESSES] [OUTPUT_PATH]";
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let n_guesses = match args.next().map(|arg| arg.parse::<usize>()) {
+        None => 8,
+        Some(Ok(n_guesses)) => n_guesses,
+        Some(Err(_)) => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2)
+        },
+    };
+    let output_path = args.next().unwrap_or_else(|| "data/opening_book.txt".to_string());
+
+    let book = generate(n_guesses).expect("Searching from the start of a game should always work");
+    let mut output = std::fs::File::create(&output_path).expect("Should be able to create the output file");
+    for guess in book {
+        output.write_all(guess.bytes()).and_then(|_| output.write_all(b"\n"))
+            .expect("Should be able to write to the output file");
+    }
+}
@@ -0,0 +1,46 @@
+//! Runs the solver as a local HTTP server; see `kilordle_droid::serve`.
+
+use std::net::TcpListener;
+use kilordle_droid::Parallelism;
+use kilordle_droid::serve::serve;
+
+const USAGE: &str = "Usage: serve [--port N] [--threads N]";
+
+struct Options {
+    port: u16,
+    threads: Option<usize>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options { port: 8537, threads: None };
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+        match flag.as_str() {
+            "--port" => options.port = value.parse().map_err(|_| format!("Invalid value for {}: {}", flag, value))?,
+            "--threads" => options.threads = Some(value.parse().map_err(|_| format!("Invalid value for {}: {}", flag, value))?),
+            _ => return Err(format!("Unknown option {}", flag)),
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            std::process::exit(2)
+        },
+    };
+
+    let parallelism = match options.threads {
+        None => Parallelism::global(),
+        Some(n_threads) => Parallelism::threads(n_threads).expect("Should be able to start threads"),
+    };
+
+    // Only listen locally, as there is no authentication.
+    let listener = TcpListener::bind(("127.0.0.1", options.port)).expect("Should be able to listen on the port");
+    println!("Listening on http://{}", listener.local_addr().expect("Listener has an address"));
+    serve(listener, &parallelism).expect("Server should keep running");
+}
@@ -0,0 +1,133 @@
+//! Searches over the solver's scoring parameters by playing simulated games,
+//! and reports the setting which finished games in the fewest guesses.
+//!
+//! With the defaults, 30 games of 20 words, it takes around five minutes on a
+//! single core. Games with more words take far longer, since every guess is
+//! scored against all the words not yet on screen.
+
+use kilordle_droid::simulate::{play_game, sample_params, Rng, SimulatedGame, Summary, DEFAULT_VISIBLE_BOARDS};
+use kilordle_droid::{Parallelism, ScoringParams};
+
+const USAGE: &str = "Usage: tune [--samples N] [--games N] [--words N] [--visible N] [--max-guesses N] [--seed N] [--threads N]";
+
+struct Options {
+    samples: usize,
+    games: usize,
+    words: usize,
+    visible: usize,
+    max_guesses: Option<usize>,
+    seed: u64,
+    threads: Option<usize>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        samples: 5,
+        games: 5,
+        words: 20,
+        visible: DEFAULT_VISIBLE_BOARDS,
+        max_guesses: None,
+        seed: 0,
+        threads: None,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+        let value: u64 = value.parse().map_err(|_| format!("Invalid value for {}: {}", flag, value))?;
+        match flag.as_str() {
+            "--samples" => options.samples = value as usize,
+            "--games" => options.games = value as usize,
+            "--words" => options.words = value as usize,
+            "--visible" => options.visible = value as usize,
+            "--max-guesses" => options.max_guesses = Some(value as usize),
+            "--seed" => options.seed = value,
+            "--threads" => options.threads = Some(value as usize),
+            _ => return Err(format!("Unknown option {}", flag)),
+        }
+    }
+    if options.games == 0 || options.words == 0 {
+        return Err("Need at least one game and one word".to_string())
+    }
+    Ok(options)
+}
+
+/// The guesses taken in the games which finished, and how many did not finish.
+struct Evaluation {
+    finished: Option<Summary>,
+    n_unfinished: usize,
+}
+
+impl Evaluation {
+    /// Fewer unfinished games first, then fewer guesses in the finished ones.
+    fn is_better_than(&self, other: &Evaluation) -> bool {
+        let mean = |evaluation: &Evaluation| evaluation.finished.map_or(f64::INFINITY, |summary| summary.mean);
+        (self.n_unfinished, mean(self)) < (other.n_unfinished, mean(other))
+    }
+
+    fn describe(&self) -> String {
+        match self.finished {
+            Some(summary) => format!(
+                "{:.1} guesses (95% CI {:.1} to {:.1}, {} games finished, {} unfinished)",
+                summary.mean, summary.mean - summary.half_width, summary.mean + summary.half_width, summary.n, self.n_unfinished,
+            ),
+            None => format!("no games finished ({} unfinished)", self.n_unfinished),
+        }
+    }
+}
+
+fn evaluate(params: &ScoringParams, options: &Options) -> Evaluation {
+    // Every setting plays the same games, so that differences are down to the parameters.
+    let mut rng = Rng::new(options.seed);
+    let max_guesses = options.max_guesses.unwrap_or(options.words * 5);
+    let mut n_unfinished = 0;
+    let finished: Vec<f64> = (0..options.games).filter_map(|_| {
+        let mut game = SimulatedGame::random(&mut rng, options.words, options.visible);
+        let n_guesses = play_game(&mut game, params, max_guesses).expect("Simulated game should always be valid");
+        if n_guesses.is_none() {
+            n_unfinished += 1;
+        }
+        n_guesses.map(|n_guesses| n_guesses as f64)
+    }).collect();
+    Evaluation {
+        finished: if finished.is_empty() { None } else { Some(Summary::of(&finished)) },
+        n_unfinished,
+    }
+}
+
+fn describe(params: &ScoringParams) -> String {
+    format!(
+        "exact={:.3} partial={:.3} bonus_log_base={:.3} visible_weight={:.3} invisible_weight={:.3}",
+        params.exact_points, params.partial_points, params.invisible_bonus_log_base, params.visible_weight, params.invisible_weight,
+    )
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            std::process::exit(2)
+        },
+    };
+
+    let parallelism = match options.threads {
+        None => Parallelism::global(),
+        Some(n_threads) => Parallelism::threads(n_threads).expect("Should be able to start threads"),
+    };
+
+    let mut rng = Rng::new(options.seed ^ 0x5eed);
+    let candidates = std::iter::once(ScoringParams::default())
+        .chain((0..options.samples).map(|_| sample_params(&mut rng)));
+
+    let mut best: Option<(ScoringParams, Evaluation)> = None;
+    for (i, params) in candidates.enumerate() {
+        let evaluation = parallelism.install(|| evaluate(&params, &options));
+        println!("{:>4}: {} with {}", i, evaluation.describe(), describe(&params));
+        if best.as_ref().is_none_or(|(_, best)| evaluation.is_better_than(best)) {
+            best = Some((params, evaluation));
+        }
+    }
+
+    let (params, evaluation) = best.expect("Always tries the default parameters");
+    println!("Best: {} with {}", evaluation.describe(), describe(&params));
+}
@@ -0,0 +1,92 @@
+//! What the solver knows about a single board.
+
+use rayon::prelude::*;
+use crate::{possible_answers, GuessResult, PickGuessError, Word, N_GUESS_RESULTS};
+
+/// The wordles which could still be the answer on a board showing
+/// `result_history` for the guesses in `guess_history`.
+///
+/// These are the words the solver scores for the board. Words which have been
+/// guessed or are in `solved_words` are left out, since the answers on
+/// different boards are all different, so a board which has been solved has no
+/// possible words left.
+pub fn possible_words(guess_history: &[Word], result_history: &[GuessResult], solved_words: &[Word]) -> Result<Vec<Word>, PickGuessError> {
+    check_history_lengths(guess_history, result_history)?;
+    Ok(possible_answers(guess_history, result_history, solved_words).collect())
+}
+
+/// The number of words [`possible_words`] would return.
+pub fn count_possible_words(guess_history: &[Word], result_history: &[GuessResult], solved_words: &[Word]) -> Result<usize, PickGuessError> {
+    check_history_lengths(guess_history, result_history)?;
+    Ok(possible_answers(guess_history, result_history, solved_words).count())
+}
+
+/// Groups `candidates` by the result `guess` would show if each were the
+/// answer, largest group first.
+pub fn partition(guess: Word, candidates: &[Word]) -> Vec<(GuessResult, Vec<Word>)> {
+    let mut by_result: Vec<Option<(GuessResult, Vec<Word>)>> = vec![None; N_GUESS_RESULTS];
+    for &word in candidates {
+        let result = GuessResult::evaluate(guess, word);
+        let index = result.index().expect("evaluate does not show unknown tiles");
+        by_result[index].get_or_insert_with(|| (result, Vec::new())).1.push(word);
+    }
+    let mut parts: Vec<_> = by_result.into_iter().flatten().collect();
+    parts.sort_by_key(|(_, words)| std::cmp::Reverse(words.len()));
+    parts
+}
+
+fn check_history_lengths(guess_history: &[Word], result_history: &[GuessResult]) -> Result<(), PickGuessError> {
+    if guess_history.len() != result_history.len() {
+        return Err(PickGuessError::HistoryLengthMismatch)
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict;
+
+    fn word(s: &str) -> Word {
+        s.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_possible_words_match_results() {
+        let guesses = [word("soare"), word("clint")];
+        let answer = word("cigar");
+        let results: Vec<_> = guesses.iter().map(|&guess| GuessResult::evaluate(guess, answer)).collect();
+        let words = possible_words(&guesses, &results, &[]).unwrap();
+        assert!(words.contains(&answer));
+        assert!(words.iter().all(|&word| guesses.iter().zip(&results).all(|(&guess, &result)| GuessResult::evaluate(guess, word) == result)));
+        assert_eq!(count_possible_words(&guesses, &results, &[]).unwrap(), words.len());
+        assert!(!possible_words(&guesses, &results, &[answer]).unwrap().contains(&answer));
+    }
+
+    #[test]
+    fn test_solved_board_has_no_possible_words() {
+        let guesses = [word("soare"), word("cigar")];
+        let results: Vec<_> = guesses.iter().map(|&guess| GuessResult::evaluate(guess, word("cigar"))).collect();
+        assert_eq!(possible_words(&guesses, &results, &[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_no_history_allows_every_wordle() {
+        assert_eq!(count_possible_words(&[], &[], &[]).unwrap(), dict::wordles().count());
+    }
+
+    #[test]
+    fn test_partition() {
+        let candidates = [word("hello"), word("jello"), word("cello"), word("world")];
+        let parts = partition(word("xylyl"), &candidates);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].1, vec![word("hello"), word("jello"), word("cello")]);
+        assert_eq!(parts[0].0, GuessResult::evaluate(word("xylyl"), word("hello")));
+        assert_eq!(parts[1].1, vec![word("world")]);
+    }
+
+    #[test]
+    fn test_history_length_mismatch() {
+        assert_eq!(possible_words(&[word("soare")], &[], &[]), Err(PickGuessError::HistoryLengthMismatch));
+    }
+}
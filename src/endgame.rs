@@ -0,0 +1,213 @@
+//! Exact search for the end of a game, when every board has only a few
+//! possible words left.
+//!
+//! The guesses considered are the words still possible on some board. The
+//! search is only used when no word is possible on more than one board, so
+//! that the answers on different boards, which are all different, are
+//! independent of each other.
+
+use std::collections::{HashMap, HashSet};
+use crate::{GuessResult, SearchLimits, Word, N_GUESS_RESULTS};
+
+/// The most possible words a board can have for the search to be used.
+pub const MAX_CANDIDATES_PER_BOARD: usize = 6;
+/// The most combinations of answers over all boards for the search to be used.
+pub const MAX_COMBINATIONS: usize = 1024;
+
+/// What the search minimises.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Objective {
+    ExpectedGuesses,
+    WorstCaseGuesses,
+}
+
+/// Finds the guess which solves all of `boards` in the fewest guesses, given
+/// the words still possible on each board, along with that number of guesses.
+///
+/// Returns `None` if there are too many possibilities to search exactly, a
+/// board has no possible words, a word is possible on more than one board, or
+/// the search is stopped by `limits`.
+pub fn solve(boards: &[Vec<Word>], objective: Objective, limits: &SearchLimits) -> Option<(Word, f64)> {
+    if boards.is_empty() || boards.iter().any(|words| words.is_empty() || words.len() > MAX_CANDIDATES_PER_BOARD) {
+        return None
+    }
+    let n_combinations = boards.iter().try_fold(1usize, |acc, words| acc.checked_mul(words.len()));
+    if n_combinations.is_none_or(|n| n > MAX_COMBINATIONS) {
+        return None
+    }
+    let mut seen = HashSet::new();
+    if !boards.iter().flatten().all(|&word| seen.insert(word)) {
+        return None
+    }
+
+    let mut search = Search { objective, limits, stopped: false, memo: HashMap::new() };
+    let res = search.best_guess(&canonical(boards.to_vec()));
+    if search.stopped { None } else { res }
+}
+
+fn canonical(mut boards: Vec<Vec<Word>>) -> Vec<Vec<Word>> {
+    boards.iter_mut().for_each(|words| words.sort_unstable());
+    boards.sort_unstable();
+    boards
+}
+
+struct Search<'a> {
+    objective: Objective,
+    limits: &'a SearchLimits,
+    /// Whether `limits` cut the search short, leaving the costs found wrong.
+    stopped: bool,
+    memo: HashMap<Vec<Vec<[u8; 5]>>, f64>,
+}
+
+impl Search<'_> {
+    fn cost(&mut self, boards: &[Vec<Word>]) -> f64 {
+        if boards.is_empty() {
+            return 0.0
+        }
+        let key: Vec<Vec<[u8; 5]>> = boards.iter().map(|words| words.iter().map(|word| *word.bytes()).collect()).collect();
+        if let Some(&cost) = self.memo.get(&key) {
+            return cost
+        }
+        let cost = match self.best_guess(boards) {
+            Some((_, cost)) => cost,
+            // The boards are not empty, so the search was stopped.
+            None => return f64::INFINITY,
+        };
+        self.memo.insert(key, cost);
+        cost
+    }
+
+    /// A lower bound on the cost of `guess`: every board it does not solve
+    /// needs at least one more guess. Since `guess` is possible on at most one
+    /// board, it solves at most one.
+    fn lower_bound(&self, boards: &[Vec<Word>], guess: Word) -> f64 {
+        let n_boards = boards.len() as f64;
+        match self.objective {
+            Objective::ExpectedGuesses => {
+                let expected_solved: f64 =
+                    boards.iter().filter(|words| words.contains(&guess)).map(|words| 1.0 / words.len() as f64).sum();
+                1.0 + n_boards - expected_solved
+            },
+            Objective::WorstCaseGuesses => {
+                let surely_solves = boards.iter().any(|words| words.as_slice() == [guess]);
+                1.0 + n_boards - if surely_solves { 1.0 } else { 0.0 }
+            },
+        }
+    }
+
+    fn best_guess(&mut self, boards: &[Vec<Word>]) -> Option<(Word, f64)> {
+        let mut guesses: Vec<(Word, f64)> = {
+            let mut words: Vec<Word> = boards.iter().flatten().cloned().collect();
+            words.sort_unstable();
+            words.dedup();
+            words.into_iter().map(|guess| (guess, self.lower_bound(boards, guess))).collect()
+        };
+        guesses.sort_by(|l, r| l.1.total_cmp(&r.1));
+
+        let mut best: Option<(Word, f64)> = None;
+        for (guess, lower_bound) in guesses {
+            if !self.limits.in_time() || self.limits.is_cancelled() {
+                self.stopped = true;
+                break
+            }
+            if best.is_some_and(|(_, best_cost)| lower_bound >= best_cost) {
+                break
+            }
+            let cost = 1.0 + self.cost_after(boards, guess);
+            if best.is_none_or(|(_, best_cost)| cost < best_cost) {
+                best = Some((guess, cost));
+            }
+        }
+        best
+    }
+
+    /// The cost of the rest of the game after `guess`, over every combination
+    /// of results it could show.
+    fn cost_after(&mut self, boards: &[Vec<Word>], guess: Word) -> f64 {
+        let partitions: Vec<Vec<Vec<Word>>> = boards.iter().map(|words| {
+            let mut by_result: Vec<Vec<Word>> = vec![Vec::new(); N_GUESS_RESULTS];
+            for &word in words {
+                let index = GuessResult::evaluate(guess, word).index().expect("evaluate does not show unknown tiles");
+                by_result[index].push(word);
+            }
+            by_result.into_iter().filter(|words| !words.is_empty()).collect()
+        }).collect();
+
+        let objective = self.objective;
+        let outcomes = outcomes(&partitions, guess);
+        let costs = outcomes.iter().map(|(next_boards, probability)| (self.cost(next_boards), probability));
+        match objective {
+            Objective::ExpectedGuesses => costs.map(|(cost, probability)| cost * probability).sum(),
+            Objective::WorstCaseGuesses => costs.map(|(cost, _)| cost).fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Every combination of results over the boards, as the boards left
+/// afterwards and the probability of that combination.
+fn outcomes(partitions: &[Vec<Vec<Word>>], guess: Word) -> Vec<(Vec<Vec<Word>>, f64)> {
+    let mut outcomes = vec![(Vec::new(), 1.0)];
+    for parts in partitions {
+        let n_words: usize = parts.iter().map(|part| part.len()).sum();
+        outcomes = outcomes.into_iter().flat_map(|(boards, probability): (Vec<Vec<Word>>, f64)| {
+            parts.iter().map(move |part| {
+                let mut boards = boards.clone();
+                if part.as_slice() != [guess] {
+                    boards.push(part.clone());
+                }
+                (canonical(boards), probability * (part.len() as f64) / (n_words as f64))
+            })
+        }).collect();
+    }
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boards(boards: &[&[&str]]) -> Vec<Vec<Word>> {
+        boards.iter().map(|words| words.iter().map(|&word| word.try_into().unwrap()).collect()).collect()
+    }
+
+    #[test]
+    fn test_known_words_take_one_guess_each() {
+        let (_, cost) = solve(&boards(&[&["hello"], &["world"]]), Objective::ExpectedGuesses, &SearchLimits::default()).unwrap();
+        assert_eq!(cost, 2.0);
+    }
+
+    #[test]
+    fn test_two_possible_words() {
+        let two_words = boards(&[&["hello", "jello"]]);
+        assert_eq!(solve(&two_words, Objective::ExpectedGuesses, &SearchLimits::default()).unwrap().1, 1.5);
+        assert_eq!(solve(&two_words, Objective::WorstCaseGuesses, &SearchLimits::default()).unwrap().1, 2.0);
+    }
+
+    #[test]
+    fn test_guess_which_splits_other_board() {
+        // Guessing "cello" solves the first board and tells apart the words on the second.
+        let (guess, cost) = solve(&boards(&[&["cello"], &["hello", "cells"]]), Objective::ExpectedGuesses, &SearchLimits::default()).unwrap();
+        assert_eq!(guess, "cello".try_into().unwrap());
+        assert_eq!(cost, 2.0);
+    }
+
+    #[test]
+    fn test_boards_sharing_words_are_not_searched() {
+        // The two answers are different, so one of "hello" and "jello" is
+        // only solved by a second guess, which treating the boards as
+        // independent would miss.
+        let shared = boards(&[&["hello", "jello"], &["hello", "jello"]]);
+        assert_eq!(solve(&shared, Objective::ExpectedGuesses, &SearchLimits::default()), None);
+    }
+
+    #[test]
+    fn test_stopped_by_limits() {
+        let limits = SearchLimits { deadline: Some(std::time::Instant::now()), cancelled: None };
+        assert_eq!(solve(&boards(&[&["hello", "jello"]]), Objective::ExpectedGuesses, &limits), None);
+    }
+
+    #[test]
+    fn test_too_many_possibilities() {
+        assert_eq!(solve(&boards(&[&["aaaaa", "bbbbb", "ccccc", "ddddd", "eeeee", "fffff", "ggggg"]]), Objective::ExpectedGuesses, &SearchLimits::default()), None);
+    }
+}
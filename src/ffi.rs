@@ -0,0 +1,215 @@
+//! A C API for the solver, declared in `include/kilordle_droid.h`.
+//!
+//! A solver keeps the state of one game: the guesses entered so far and the
+//! results on screen after the last of them. Every function returns a status
+//! rather than panicking across the boundary.
+
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use crate::opening_book::N_STARTING_WORDS;
+use crate::{pick_next_guess_until, GuessResult, PickGuessError, ScoringParams, SearchLimits, Word, WORD_LENGTH};
+
+/// The number of letters in a word.
+pub const KILORDLE_WORD_LENGTH: usize = 5;
+const _: () = assert!(KILORDLE_WORD_LENGTH == WORD_LENGTH);
+
+/// The state of a game being solved. Only used through pointers.
+pub struct KilordleSolver {
+    guess_history: Vec<Word>,
+    visible_results: Vec<Vec<GuessResult>>,
+    n_remaining_words: usize,
+}
+
+/// The outcome of a call.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KilordleStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidWord,
+    InvalidResult,
+    HistoryLengthMismatch,
+    InsufficientRemainingWords,
+    NoPossibleGuesses,
+    InternalError,
+}
+
+impl From<PickGuessError> for KilordleStatus {
+    fn from(err: PickGuessError) -> Self {
+        match err {
+            PickGuessError::InsufficientRemainingWords => KilordleStatus::InsufficientRemainingWords,
+            PickGuessError::HistoryLengthMismatch => KilordleStatus::HistoryLengthMismatch,
+            PickGuessError::NoPossibleGuesses => KilordleStatus::NoPossibleGuesses,
+            PickGuessError::Cancelled | PickGuessError::OutOfTime => KilordleStatus::InternalError,
+        }
+    }
+}
+
+/// Runs `f`, turning a panic into `KilordleStatus::InternalError`.
+fn guarded(f: impl FnOnce() -> Result<(), KilordleStatus>) -> KilordleStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => KilordleStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => KilordleStatus::InternalError,
+    }
+}
+
+/// # Safety
+/// `s` must be null or a valid NUL-terminated string.
+unsafe fn c_str<'a>(s: *const c_char) -> Result<&'a str, KilordleStatus> {
+    if s.is_null() {
+        return Err(KilordleStatus::NullPointer)
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| KilordleStatus::InvalidWord)
+}
+
+/// A result history written as five characters per guess, each ' ', 'o', 'O' or '?'.
+fn parse_result_history(s: &str) -> Result<Vec<GuessResult>, KilordleStatus> {
+    if !s.len().is_multiple_of(WORD_LENGTH) {
+        return Err(KilordleStatus::InvalidResult)
+    }
+    s.as_bytes().chunks(WORD_LENGTH).map(|chunk| {
+        let chunk = std::str::from_utf8(chunk).map_err(|_| KilordleStatus::InvalidResult)?;
+        chunk.parse().map_err(|_| KilordleStatus::InvalidResult)
+    }).collect()
+}
+
+/// Creates a solver for a new game, which must be freed with
+/// `kilordle_solver_free`.
+#[no_mangle]
+pub extern "C" fn kilordle_solver_new() -> *mut KilordleSolver {
+    Box::into_raw(Box::new(KilordleSolver {
+        guess_history: Vec::new(),
+        visible_results: Vec::new(),
+        n_remaining_words: N_STARTING_WORDS,
+    }))
+}
+
+/// Frees a solver. Does nothing if `solver` is null.
+///
+/// # Safety
+/// `solver` must be null or come from `kilordle_solver_new` and not have been
+/// freed already.
+#[no_mangle]
+pub unsafe extern "C" fn kilordle_solver_free(solver: *mut KilordleSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Records that `guess` was entered, and what is on screen afterwards.
+///
+/// `result_histories` holds `n_boards` strings, one for each board on screen,
+/// with five characters for each guess entered so far including this one:
+/// ' ' for no match, 'o' for a partial match, 'O' for an exact match and '?'
+/// for a tile whose colour was not read.
+/// The solver is left unchanged if anything is invalid.
+///
+/// # Safety
+/// `solver` must be a valid solver, `guess` a NUL-terminated string, and
+/// `result_histories` must point to `n_boards` NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn kilordle_solver_add_turn(
+    solver: *mut KilordleSolver,
+    guess: *const c_char,
+    result_histories: *const *const c_char,
+    n_boards: usize,
+    n_remaining_words: usize,
+) -> KilordleStatus {
+    guarded(|| {
+        let solver = solver.as_mut().ok_or(KilordleStatus::NullPointer)?;
+        let guess = Word::try_from(c_str(guess)?).map_err(|_| KilordleStatus::InvalidWord)?;
+        if result_histories.is_null() && n_boards > 0 {
+            return Err(KilordleStatus::NullPointer)
+        }
+        let visible_results = (0..n_boards)
+            .map(|i| parse_result_history(c_str(*result_histories.add(i))?))
+            .collect::<Result<Vec<_>, _>>()?;
+        if visible_results.iter().any(|results| results.len() != solver.guess_history.len() + 1) {
+            return Err(KilordleStatus::HistoryLengthMismatch)
+        }
+        solver.guess_history.push(guess);
+        solver.visible_results = visible_results;
+        solver.n_remaining_words = n_remaining_words;
+        Ok(())
+    })
+}
+
+/// Finds the next guess, and writes it to `guess_out` as a NUL-terminated
+/// string of `KILORDLE_WORD_LENGTH` letters.
+///
+/// # Safety
+/// `solver` must be a valid solver and `guess_out` must have room for
+/// `KILORDLE_WORD_LENGTH + 1` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn kilordle_solver_suggest(solver: *const KilordleSolver, guess_out: *mut c_char) -> KilordleStatus {
+    guarded(|| {
+        let solver = solver.as_ref().ok_or(KilordleStatus::NullPointer)?;
+        if guess_out.is_null() {
+            return Err(KilordleStatus::NullPointer)
+        }
+        let guess = pick_next_guess_until(
+            &solver.guess_history, &solver.visible_results, solver.n_remaining_words, &[], &ScoringParams::default(), &SearchLimits::default(),
+        )?;
+        let guess_out = std::slice::from_raw_parts_mut(guess_out as *mut u8, KILORDLE_WORD_LENGTH + 1);
+        guess_out[..KILORDLE_WORD_LENGTH].copy_from_slice(guess.bytes());
+        guess_out[KILORDLE_WORD_LENGTH] = 0;
+        Ok(())
+    })
+}
+
+/// A description of `status`, as a static NUL-terminated string.
+#[no_mangle]
+pub extern "C" fn kilordle_status_message(status: KilordleStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        KilordleStatus::Ok => b"OK\0",
+        KilordleStatus::NullPointer => b"A required pointer was null\0",
+        KilordleStatus::InvalidWord => b"Words must be exactly 5 letters a..z\0",
+        KilordleStatus::InvalidResult => b"Results must be 5 characters per guess, each ' ', 'o', 'O' or '?'\0",
+        KilordleStatus::HistoryLengthMismatch => b"Length of histories are different\0",
+        KilordleStatus::InsufficientRemainingWords => b"Number of remaining words is insufficient\0",
+        KilordleStatus::NoPossibleGuesses => b"Failed to find any words to be possible guesses\0",
+        KilordleStatus::InternalError => b"Internal error in the solver\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_solver_round_trip() {
+        unsafe {
+            let solver = kilordle_solver_new();
+            let mut guess = [0 as c_char; KILORDLE_WORD_LENGTH + 1];
+            assert_eq!(kilordle_solver_suggest(solver, guess.as_mut_ptr()), KilordleStatus::Ok);
+            assert_eq!(CStr::from_ptr(guess.as_ptr()).to_str().unwrap(), "soare");
+
+            let soare = CString::new("soare").unwrap();
+            let results = [CString::new("  o o").unwrap()];
+            let result_ptrs: Vec<_> = results.iter().map(|s| s.as_ptr()).collect();
+            assert_eq!(kilordle_solver_add_turn(solver, soare.as_ptr(), result_ptrs.as_ptr(), 1, 1000), KilordleStatus::Ok);
+            assert_eq!((*solver).visible_results, vec![vec!["  o o".parse().unwrap()]]);
+            kilordle_solver_free(solver);
+        }
+    }
+
+    #[test]
+    fn test_invalid_turns_leave_solver_unchanged() {
+        unsafe {
+            let solver = kilordle_solver_new();
+            let soare = CString::new("soare").unwrap();
+            let invalid = CString::new("SOARE").unwrap();
+            let wrong_length = [CString::new("  o o     ").unwrap()];
+            let wrong_length: Vec<_> = wrong_length.iter().map(|s| s.as_ptr()).collect();
+            assert_eq!(kilordle_solver_add_turn(solver, invalid.as_ptr(), std::ptr::null(), 0, 1000), KilordleStatus::InvalidWord);
+            assert_eq!(kilordle_solver_add_turn(solver, soare.as_ptr(), wrong_length.as_ptr(), 1, 1000), KilordleStatus::HistoryLengthMismatch);
+            assert_eq!(kilordle_solver_add_turn(solver, soare.as_ptr(), std::ptr::null(), 1, 1000), KilordleStatus::NullPointer);
+            assert!((*solver).guess_history.is_empty());
+            assert_eq!(kilordle_solver_suggest(std::ptr::null(), std::ptr::null_mut()), KilordleStatus::NullPointer);
+            kilordle_solver_free(solver);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! A record of the games played, as JSON lines with one line per turn.
+//!
+//! Each line carries the version of the format it was written in, so that
+//! older logs can still be told apart once the format changes.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+#[cfg(feature = "python")]
+use pyo3::exceptions::{PyIOError, PyValueError};
+#[cfg(feature = "python")]
+use pyo3::PyErr;
+use serde::{Deserialize, Serialize};
+use crate::replay::GameTurn;
+use crate::{GuessResult, Word};
+
+/// The version of the format written by this version of the crate.
+pub const GAME_LOG_VERSION: u32 = 1;
+
+/// One turn of a game.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TurnRecord {
+    pub version: u32,
+    /// When the guess was entered, in seconds since the epoch.
+    pub timestamp: f64,
+    pub guess: Word,
+    /// The results on each board on screen for the guesses before this turn.
+    pub visible_results: Vec<Vec<GuessResult>>,
+    pub n_remaining_words: usize,
+    /// The guess the solver picked, if it was asked.
+    pub solver_guess: Option<Word>,
+    /// How long the solver took to pick its guess.
+    pub solve_seconds: Option<f64>,
+}
+
+impl TurnRecord {
+    /// The turn as needed for `replay::replay`.
+    pub fn game_turn(&self) -> GameTurn {
+        GameTurn {
+            visible_results: self.visible_results.clone(),
+            n_remaining_words: self.n_remaining_words,
+            guess: self.guess,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GameLogError {
+    Io(std::io::Error),
+    InvalidRecord { line: usize, error: serde_json::Error },
+    UnsupportedVersion { line: usize, version: u32 },
+}
+
+impl Display for GameLogError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameLogError::Io(err) => write!(f, "Failed to access game log: {}", err),
+            GameLogError::InvalidRecord { line, error } => write!(f, "Invalid record on line {} of game log: {}", line, error),
+            GameLogError::UnsupportedVersion { line, version } =>
+                write!(f, "Record on line {} of game log has version {}, but only version {} is supported", line, version, GAME_LOG_VERSION),
+        }
+    }
+}
+
+impl Error for GameLogError {
+
+}
+
+impl From<std::io::Error> for GameLogError {
+    fn from(err: std::io::Error) -> Self {
+        GameLogError::Io(err)
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<GameLogError> for PyErr {
+    fn from(err: GameLogError) -> Self {
+        match err {
+            GameLogError::Io(_) => PyIOError::new_err(err.to_string()),
+            GameLogError::InvalidRecord { .. } | GameLogError::UnsupportedVersion { .. } => PyValueError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// Reads every turn in a log, skipping blank lines.
+pub fn read_game_log(reader: impl BufRead) -> Result<Vec<TurnRecord>, GameLogError> {
+    let mut records = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+        let record: TurnRecord = serde_json::from_str(&line)
+            .map_err(|error| GameLogError::InvalidRecord { line: i + 1, error })?;
+        if record.version != GAME_LOG_VERSION {
+            return Err(GameLogError::UnsupportedVersion { line: i + 1, version: record.version })
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Writes one turn as a line of the log.
+pub fn write_turn_record(writer: &mut impl Write, record: &TurnRecord) -> Result<(), GameLogError> {
+    serde_json::to_writer(&mut *writer, record).map_err(|err| GameLogError::Io(err.into()))?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(s: &str) -> Word {
+        s.try_into().unwrap()
+    }
+
+    fn record(guess: &str, visible_results: Vec<Vec<GuessResult>>) -> TurnRecord {
+        TurnRecord {
+            version: GAME_LOG_VERSION,
+            timestamp: 1650000000.5,
+            guess: word(guess),
+            visible_results,
+            n_remaining_words: 1000,
+            solver_guess: Some(word(guess)),
+            solve_seconds: Some(0.25),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let records = vec![
+            record("soare", vec![vec![]]),
+            record("clint", vec![vec![GuessResult::evaluate(word("soare"), word("cigar"))]]),
+        ];
+        let mut log = Vec::new();
+        records.iter().for_each(|record| write_turn_record(&mut log, record).unwrap());
+        assert_eq!(log.iter().filter(|&&b| b == b'\n').count(), 2);
+        assert_eq!(read_game_log(log.as_slice()).unwrap(), records);
+    }
+
+    #[test]
+    fn test_format() {
+        let mut log = Vec::new();
+        write_turn_record(&mut log, &record("clint", vec![vec![GuessResult::evaluate(word("soare"), word("cigar"))]])).unwrap();
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "{\"version\":1,\"timestamp\":1650000000.5,\"guess\":\"clint\",\"visible_results\":[[\"  oo \"]],\"n_remaining_words\":1000,\"solver_guess\":\"clint\",\"solve_seconds\":0.25}\n",
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let line = "{\"version\":2,\"timestamp\":0.0,\"guess\":\"soare\",\"visible_results\":[],\"n_remaining_words\":1000,\"solver_guess\":null,\"solve_seconds\":null}\n";
+        assert!(matches!(
+            read_game_log(format!("\n{}", line).as_bytes()),
+            Err(GameLogError::UnsupportedVersion { line: 2, version: 2 }),
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_words() {
+        let line = "{\"version\":1,\"timestamp\":0.0,\"guess\":\"SOARE\",\"visible_results\":[],\"n_remaining_words\":1000,\"solver_guess\":null,\"solve_seconds\":null}";
+        assert!(matches!(read_game_log(line.as_bytes()), Err(GameLogError::InvalidRecord { line: 1, .. })));
+    }
+}
@@ -0,0 +1,158 @@
+//! Checking the boards read from the game's page before they reach the solver.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::{FromPyObject, PyErr};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use crate::{dict, GuessResult, GuessResultOfStringError, Word, WordOfStringError};
+
+/// One board as read from the page by `read_results.js`, with a row for each
+/// guess made.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize), serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+pub struct Column {
+    #[cfg_attr(feature = "python", pyo3(item("guessHistory")))]
+    pub guess_history: Vec<String>,
+    #[cfg_attr(feature = "python", pyo3(item("resultHistory")))]
+    pub result_history: Vec<String>,
+}
+
+/// The guesses made and the results on each board on screen.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct GameState {
+    pub guess_history: Vec<Word>,
+    pub result_histories: Vec<Vec<GuessResult>>,
+}
+
+/// What was wrong with the columns read, with the column and row it was
+/// found in counting from zero.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GameStateError {
+    RowCountMismatch { column: usize, n_guesses: usize, n_results: usize },
+    GuessHistoryMismatch { column: usize },
+    InvalidGuess { column: usize, row: usize, guess: String, error: WordOfStringError },
+    UnknownWord { column: usize, row: usize, guess: Word },
+    InvalidResult { column: usize, row: usize, result: String, error: GuessResultOfStringError },
+}
+
+impl Display for GameStateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameStateError::RowCountMismatch { column, n_guesses, n_results } =>
+                write!(f, "Column {} has {} guesses but {} results", column, n_guesses, n_results),
+            GameStateError::GuessHistoryMismatch { column } =>
+                write!(f, "Column {} has different guesses from column 0", column),
+            GameStateError::InvalidGuess { column, row, guess, error } =>
+                write!(f, "Column {} row {} has guess {:?}: {}", column, row, guess, error),
+            GameStateError::UnknownWord { column, row, guess } =>
+                write!(f, "Column {} row {} has guess \"{}\", which is not an allowed word", column, row, guess),
+            GameStateError::InvalidResult { column, row, result, error } =>
+                write!(f, "Column {} row {} has result {:?}: {}", column, row, result, error),
+        }
+    }
+}
+
+impl Error for GameStateError {
+
+}
+
+#[cfg(feature = "python")]
+impl From<GameStateError> for PyErr {
+    fn from(err: GameStateError) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+impl GameState {
+    /// Checks the columns read from the page and converts them for the solver.
+    ///
+    /// Every column must have a result for each of its guesses, the guesses
+    /// must be the same in every column and all be allowed words, and the
+    /// results must be written with ' ', 'o', 'O' and '?'.
+    pub fn from_columns(columns: &[Column]) -> Result<Self, GameStateError> {
+        let first = match columns.first() {
+            Some(first) => first,
+            None => return Ok(GameState::default()),
+        };
+        for (i, column) in columns.iter().enumerate() {
+            if column.guess_history.len() != column.result_history.len() {
+                return Err(GameStateError::RowCountMismatch { column: i, n_guesses: column.guess_history.len(), n_results: column.result_history.len() })
+            }
+            if column.guess_history != first.guess_history {
+                return Err(GameStateError::GuessHistoryMismatch { column: i })
+            }
+        }
+
+        let allowed_words: BTreeSet<Word> = dict::wordles().chain(dict::other_words()).collect();
+        let guess_history = first.guess_history.iter().enumerate().map(|(row, guess)| {
+            let word = Word::try_from(guess.as_str())
+                .map_err(|error| GameStateError::InvalidGuess { column: 0, row, guess: guess.clone(), error })?;
+            if !allowed_words.contains(&word) {
+                return Err(GameStateError::UnknownWord { column: 0, row, guess: word })
+            }
+            Ok(word)
+        }).collect::<Result<_, _>>()?;
+
+        let result_histories = columns.iter().enumerate().map(|(column, it)| {
+            it.result_history.iter().enumerate().map(|(row, result)| {
+                result.parse::<GuessResult>()
+                    .map_err(|error| GameStateError::InvalidResult { column, row, result: result.clone(), error })
+            }).collect()
+        }).collect::<Result<_, _>>()?;
+
+        Ok(GameState { guess_history, result_histories })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(guesses: &[&str], results: &[&str]) -> Column {
+        Column {
+            guess_history: guesses.iter().map(|s| s.to_string()).collect(),
+            result_history: results.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_valid_columns() {
+        let state = GameState::from_columns(&[
+            column(&["soare", "clint"], &["  o o", "O    "]),
+            column(&["soare", "clint"], &["     ", " OO  "]),
+        ]).unwrap();
+        assert_eq!(state.guess_history, vec![Word::try_from("soare").unwrap(), Word::try_from("clint").unwrap()]);
+        assert_eq!(state.result_histories[1][1], " OO  ".parse().unwrap());
+        assert_eq!(GameState::from_columns(&[]).unwrap(), GameState::default());
+    }
+
+    #[test]
+    fn test_invalid_columns() {
+        assert_eq!(
+            GameState::from_columns(&[column(&["soare"], &["  o o"]), column(&["soare", "clint"], &["     "])]),
+            Err(GameStateError::RowCountMismatch { column: 1, n_guesses: 2, n_results: 1 }),
+        );
+        assert_eq!(
+            GameState::from_columns(&[column(&["soare"], &["  o o"]), column(&["crane"], &["     "])]),
+            Err(GameStateError::GuessHistoryMismatch { column: 1 }),
+        );
+        assert_eq!(
+            GameState::from_columns(&[column(&["soare", "SOARE"], &["  o o", "     "])]),
+            Err(GameStateError::InvalidGuess { column: 0, row: 1, guess: "SOARE".to_string(), error: WordOfStringError::InvalidLetter }),
+        );
+        assert_eq!(
+            GameState::from_columns(&[column(&["zzzzz"], &["     "])]),
+            Err(GameStateError::UnknownWord { column: 0, row: 0, guess: Word::try_from("zzzzz").unwrap() }),
+        );
+        assert_eq!(
+            GameState::from_columns(&[column(&["soare"], &["  o o"]), column(&["soare"], &["  x  "])]),
+            Err(GameStateError::InvalidResult { column: 1, row: 0, result: "  x  ".to_string(), error: GuessResultOfStringError::InvalidCharacter }),
+        );
+    }
+}
@@ -1,15 +1,113 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::ops::Deref;
-use pyo3::exceptions::PyValueError;
-use pyo3::prelude::*;
-use word::{Word, WORD_LENGTH};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+pub use word::{ParseWordError, Word, WordOfStringError, WORD_LENGTH};
 use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 
 mod word;
 mod dict;
+pub mod simulate;
+pub mod lookahead;
+pub mod endgame;
+#[cfg(feature = "python")]
+mod background;
+#[cfg(feature = "python")]
+mod py_types;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod parallelism;
+pub mod opening_book;
+pub mod board;
+pub mod replay;
+#[cfg(feature = "serde")]
+pub mod game_log;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub mod serve;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ffi;
+pub mod game_state;
+#[cfg(feature = "html-snapshot")]
+pub mod page_snapshot;
+#[cfg(feature = "screenshot")]
+pub mod screenshot;
+
+pub use parallelism::Parallelism;
 
 const MAX_SCORE: u8 = 3 * (WORD_LENGTH as u8);
 
+/// The tunable constants used when scoring guesses.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ScoringParams {
+    /// Points for a letter of a possible word whose position is known.
+    pub exact_points: f64,
+    /// Points for a letter of a possible word which is known to be present.
+    pub partial_points: f64,
+    /// Base of the logarithm in the bonus given to the words not on screen,
+    /// which is `n / log(n)` once there are at least this many of them.
+    pub invisible_bonus_log_base: f64,
+    /// Multiplier for the total score over the boards on screen.
+    pub visible_weight: f64,
+    /// Multiplier for the score over the words not on screen.
+    pub invisible_weight: f64,
+}
+
+impl Default for ScoringParams {
+    fn default() -> Self {
+        ScoringParams {
+            exact_points: 3.0,
+            partial_points: 1.0,
+            invisible_bonus_log_base: 5.0,
+            visible_weight: 1.0,
+            invisible_weight: 1.0,
+        }
+    }
+}
+
+impl ScoringParams {
+    fn invisible_words_bonus(&self, n_invisible_words: usize) -> f64 {
+        let n = n_invisible_words as f64;
+        if n >= self.invisible_bonus_log_base {
+            n / n.log(self.invisible_bonus_log_base)
+        } else {
+            n
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PickGuessError {
+    InsufficientRemainingWords,
+    HistoryLengthMismatch,
+    NoPossibleGuesses,
+    Cancelled,
+    OutOfTime,
+}
+
+impl Display for PickGuessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PickGuessError::InsufficientRemainingWords => write!(f, "Number of remaining words is insufficient"),
+            PickGuessError::HistoryLengthMismatch => write!(f, "Length of histories are different"),
+            PickGuessError::NoPossibleGuesses => write!(f, "Failed to find any words to be possible guesses"),
+            PickGuessError::Cancelled => write!(f, "Search for a guess was cancelled"),
+            PickGuessError::OutOfTime => write!(f, "Deadline passed before any possible answers were found"),
+        }
+    }
+}
+
+impl Error for PickGuessError {
+
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 struct ScoringState {
     word: Word,
@@ -40,28 +138,87 @@ impl ScoringState {
     fn current_score(&self) -> u8 {
         self.score_at_position.iter().sum()
     }
+
+    /// Number of positions known exactly and number only known to be present.
+    fn match_counts(&self) -> (u64, u64) {
+        self.score_at_position.iter().fold((0, 0), |(exact, partial), &score| {
+            match score {
+                3 => (exact + 1, partial),
+                1 => (exact, partial + 1),
+                _ => (exact, partial),
+            }
+        })
+    }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+/// How a letter of a guess matches the answer.
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 #[repr(u8)]
-enum LetterMatch {
+pub enum LetterMatch {
     Nothing = 0,
     Partial,
-    Exact
+    Exact,
+    /// The colour of the tile was not read, so it could be any of the others.
+    Unknown,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-struct GuessResult([LetterMatch; WORD_LENGTH]);
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GuessResult([LetterMatch; WORD_LENGTH]);
+
+/// The number of distinct guess results.
+const N_GUESS_RESULTS: usize = 243;
 
 impl GuessResult {
+    /// A distinct number in `0..N_GUESS_RESULTS` for each result without
+    /// unknown tiles, such as those from `evaluate`, or None if it has any.
+    fn index(&self) -> Option<usize> {
+        self.0.iter().rev().try_fold(0, |acc, &r| (r != LetterMatch::Unknown).then(|| acc * 3 + r as usize))
+    }
+
+    /// The result the game shows for `guess` when the hidden word is `word`.
+    pub fn evaluate(guess: Word, word: Word) -> Self {
+        let guess = guess.bytes();
+        let mut word = *word.bytes();
+        let mut res = [LetterMatch::Nothing; WORD_LENGTH];
+
+        for (i, r) in res.iter_mut().enumerate() {
+            if guess[i] == word[i] {
+                *r = LetterMatch::Exact;
+                word[i] = b' ';
+            }
+        }
+
+        for (i, r) in res.iter_mut().enumerate() {
+            if *r == LetterMatch::Exact { continue }
+            if let Some(idx) = word.iter().position(|&x| x == guess[i]) {
+                *r = LetterMatch::Partial;
+                word[idx] = b' ';
+            }
+        }
+
+        GuessResult(res)
+    }
+
     fn is_possible(&self, guess: Word, word: Word) -> bool {
+        // A tile which was not read could have shown any match. The game only
+        // shows one of several ways to mark repeated letters, so this tries
+        // each match rather than comparing with `evaluate`.
+        if let Some(i) = self.0.iter().position(|&r| r == LetterMatch::Unknown) {
+            return [LetterMatch::Nothing, LetterMatch::Partial, LetterMatch::Exact].iter().any(|&r| {
+                let mut result = *self;
+                result.0[i] = r;
+                result.is_possible(guess, word)
+            })
+        }
+
         let guess = guess.bytes();
         let mut word = *word.bytes();
 
         // Exact matches
         for (i, &r) in self.0.iter().enumerate() {
             match r {
-                LetterMatch::Nothing | LetterMatch::Partial => continue,
+                LetterMatch::Nothing | LetterMatch::Partial | LetterMatch::Unknown => continue,
                 LetterMatch::Exact => {
                     if guess[i] == word[i] {
                         word[i] = b' '
@@ -75,7 +232,7 @@ impl GuessResult {
         // Partial matches
         for (i, &r) in self.0.iter().enumerate() {
             match r {
-                LetterMatch::Nothing | LetterMatch::Exact => continue,
+                LetterMatch::Nothing | LetterMatch::Exact | LetterMatch::Unknown => continue,
                 LetterMatch::Partial => {
                     if guess[i] == word[i] { return false; }
                     match word.iter().position(|&x| x == guess[i]) {
@@ -89,7 +246,7 @@ impl GuessResult {
         // No matches
         for (i, &r) in self.0.iter().enumerate() {
             match r {
-                LetterMatch::Partial | LetterMatch::Exact => continue,
+                LetterMatch::Partial | LetterMatch::Exact | LetterMatch::Unknown => continue,
                 LetterMatch::Nothing => {
                     if word.iter().any(|&x| x == guess[i]) {
                         return false
@@ -107,103 +264,412 @@ impl GuessResult {
 }
 
 
-fn pick_next_guess_inner(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize) -> Result<Word, PyErr> {
-    let n_invisible_words = match n_remaining_words.checked_sub(visible_results.len()) {
-        Some(x) => x,
-        None => return Err(PyValueError::new_err("Number of remaining words is insufficient")),
+/// Limits on how long a search for a guess may run.
+#[derive(Clone, Default, Debug)]
+pub struct SearchLimits {
+    /// Stop scoring guesses after this, and use the best found so far.
+    pub deadline: Option<Instant>,
+    /// Give up with `PickGuessError::Cancelled` once this is set.
+    pub cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl SearchLimits {
+    fn in_time(&self) -> bool {
+        self.deadline.is_none_or(|deadline| Instant::now() < deadline)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.as_ref().is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    }
+
+    fn check_cancelled(&self) -> Result<(), PickGuessError> {
+        if self.is_cancelled() { Err(PickGuessError::Cancelled) } else { Ok(()) }
+    }
+}
+
+/// Whether `word` may be an answer not found yet. Words which have been
+/// guessed have either solved their board or were not answers anywhere, and
+/// the answers on different boards are all different.
+fn is_unguessed_answer(guess_history: &[Word], solved_words: &[Word], word: Word) -> bool {
+    !guess_history.contains(&word) && !solved_words.contains(&word)
+}
+
+/// Whether `word` could still be the answer on a board showing `result_history`
+/// for the guesses in `guess_history`.
+fn is_possible_answer(guess_history: &[Word], result_history: &[GuessResult], solved_words: &[Word], word: Word) -> bool {
+    is_unguessed_answer(guess_history, solved_words, word) && GuessResult::history_is_possible(guess_history, result_history, word)
+}
+
+/// The wordles which could still be the answer on a board, as the solver sees it.
+fn possible_answers<'a>(guess_history: &'a [Word], result_history: &'a [GuessResult], solved_words: &'a [Word]) -> impl ParallelIterator<Item=Word> + 'a {
+    dict::wordles().into_par_iter().filter(move |&word| is_possible_answer(guess_history, result_history, solved_words, word))
+}
+
+/// The words which could still be the answers, for the boards on screen and
+/// for the words not on screen.
+struct Candidates {
+    visible: Vec<Vec<ScoringState>>,
+    invisible: Vec<ScoringState>,
+    n_invisible_words: usize,
+    invisible_words_bonus: f64,
+}
+
+impl Candidates {
+    /// The words on each board are those of `possible_answers`, and the words
+    /// not on screen are limited to those which have not been guessed.
+    ///
+    /// Words not reached before the deadline in `limits` are left out, so
+    /// the candidates may be incomplete once it has passed.
+    fn for_history(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize, solved_words: &[Word], params: &ScoringParams, limits: &SearchLimits) -> Result<Self, PickGuessError> {
+        let n_invisible_words = match n_remaining_words.checked_sub(visible_results.len()) {
+            Some(x) => x,
+            None => return Err(PickGuessError::InsufficientRemainingWords),
+        };
+        let invisible_words_bonus = params.invisible_words_bonus(n_invisible_words);
+
+        if visible_results.iter().any(|x| x.len() != guess_history.len()) {
+            return Err(PickGuessError::HistoryLengthMismatch)
+        }
+
+        let mut possible_invisible_words: Vec<_> =
+            dict::wordles()
+                .into_par_iter()
+                .filter(|_| limits.in_time() && !limits.is_cancelled())
+                .map(|word| {
+                    let mut state = ScoringState::for_word(word);
+                    state.add_history_items(guess_history);
+                    state
+                })
+                .filter(|state| is_unguessed_answer(guess_history, solved_words, state.word))
+                .collect();
+        limits.check_cancelled()?;
+
+        let possible_visible_words: Vec<_> =
+            visible_results.par_iter().map(|x| {
+                let result_history: &[GuessResult] = x.deref();
+                let possible_words: Vec<_> = {
+                    possible_invisible_words.par_iter()
+                        .cloned()
+                        .filter(|state| is_possible_answer(guess_history, result_history, solved_words, state.word))
+                        .collect()
+                };
+                possible_words
+            }).collect();
+
+        if let Some(maximum_invisible_score) = possible_visible_words.iter().map(|possible_words| possible_words.iter().map(|word| word.current_score()).max().unwrap_or(MAX_SCORE)).min() {
+            possible_invisible_words.retain(|word| word.current_score() <= maximum_invisible_score);
+        }
+
+        Ok(Candidates {
+            visible: possible_visible_words,
+            invisible: possible_invisible_words,
+            n_invisible_words,
+            invisible_words_bonus,
+        })
+    }
+
+    /// The best guess by exact search, if every remaining word is on screen
+    /// and there are few enough possibilities.
+    fn endgame_guess(&self, limits: &SearchLimits) -> Option<Word> {
+        if self.n_invisible_words > 0 {
+            return None
+        }
+        let boards: Vec<Vec<Word>> =
+            self.visible.iter().map(|possible_words| possible_words.iter().map(|state| state.word).collect()).collect();
+        endgame::solve(&boards, endgame::Objective::ExpectedGuesses, limits).map(|(guess, _)| guess)
+    }
+
+    /// The answer of a board on screen with only one possible word left.
+    ///
+    /// Once a board's answer is known, guessing other words can tell nothing
+    /// more about it, so the scoring would never pick it.
+    fn known_answer(&self) -> Option<Word> {
+        self.known_answers().next()
+    }
+
+    /// The answers of every board on screen with only one possible word left.
+    fn known_answers(&self) -> impl Iterator<Item=Word> + '_ {
+        self.visible.iter().filter(|possible_words| possible_words.len() == 1).map(|possible_words| possible_words[0].word)
+    }
+
+    /// `guess` if the search found one, and otherwise, if it ran out of time
+    /// before scoring anything, the possible answer which most is known
+    /// about, on screen if any are.
+    fn or_best_known_word(&self, guess: Option<Word>, limits: &SearchLimits) -> Result<Word, PickGuessError> {
+        if guess.is_some() || limits.in_time() {
+            return guess.ok_or(PickGuessError::NoPossibleGuesses)
+        }
+        let best = |words: &mut dyn Iterator<Item=&ScoringState>| words.max_by_key(|state| state.current_score()).map(|state| state.word);
+        best(&mut self.visible.iter().flatten()).or_else(|| best(&mut self.invisible.iter()))
+            .ok_or(PickGuessError::OutOfTime)
+    }
+
+    /// Counts `guess` as made without knowing its results, so that what it
+    /// would reveal is not scored again for later guesses.
+    fn add_unseen_guess(&mut self, guess: Word) {
+        self.visible.par_iter_mut().flatten().chain(self.invisible.par_iter_mut())
+            .for_each(|state| state.add_history_item(guess));
+    }
+
+    /// The known answers come first, then the guess from the exact search if
+    /// every remaining word is on screen, and the rest are picked by score.
+    fn pick_unseen_guesses(&mut self, params: &ScoringParams, n_guesses: usize) -> Result<Vec<Word>, PickGuessError> {
+        let mut guesses = Vec::with_capacity(n_guesses);
+        let forced: Vec<Word> = self.known_answers().chain(self.endgame_guess(&SearchLimits::default())).collect();
+        for guess in forced {
+            if guesses.len() < n_guesses && !guesses.contains(&guess) {
+                self.add_unseen_guess(guess);
+                guesses.push(guess);
+            }
+        }
+        while guesses.len() < n_guesses {
+            let guess = best_scoring(all_guesses().filter(|guess| !guesses.contains(guess)).map(|guess| {
+                (guess, self.score(guess, params))
+            }))?;
+            self.add_unseen_guess(guess);
+            guesses.push(guess);
+        }
+        Ok(guesses)
+    }
+
+    fn invisible_score(&self, guess: Word, params: &ScoringParams) -> f64 {
+        params.invisible_weight * average_score(&self.invisible, guess, params) * self.invisible_words_bonus
+    }
+
+    fn visible_score(&self, guess: Word, params: &ScoringParams) -> f64 {
+        let visible_score =
+            self.visible.iter().map(|possible_words| {
+                average_score(possible_words, guess, params)
+            }).sum::<f64>();
+        params.visible_weight * visible_score
+    }
+
+    fn score(&self, guess: Word, params: &ScoringParams) -> f64 {
+        self.visible_score(guess, params) + self.invisible_score(guess, params)
+    }
+}
+
+/// The average score over `possible_words` after `extra_guess`, or 0 if there
+/// are none, as on a board whose answer has been ruled out.
+fn average_score(possible_words: &[ScoringState], extra_guess: Word, params: &ScoringParams) -> f64 {
+    if possible_words.is_empty() {
+        return 0.0
+    }
+    let (total_exact, total_partial) =
+        possible_words.par_iter().map(|state| {
+            let mut state = state.clone();
+            state.add_history_item(extra_guess);
+            state.match_counts()
+        }).reduce(|| (0, 0), |l, r| (l.0 + r.0, l.1 + r.1));
+    let total_score = params.exact_points * (total_exact as f64) + params.partial_points * (total_partial as f64);
+    total_score / (possible_words.len() as f64)
+}
+
+/// Every word which is allowed as a guess.
+fn all_guesses() -> impl ParallelIterator<Item=Word> {
+    dict::wordles().into_par_iter().chain(dict::other_words().into_par_iter())
+}
+
+fn best_of(l: (Word, f64), r: (Word, f64)) -> (Word, f64) {
+    if r.1 > l.1 { r } else { l }
+}
+
+fn best_scoring(scored: impl ParallelIterator<Item=(Word, f64)>) -> Result<Word, PickGuessError> {
+    let res = scored.reduce_with(best_of);
+    res.map(|word| word.0).ok_or(PickGuessError::NoPossibleGuesses)
+}
+
+pub fn pick_next_guess_inner(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize) -> Result<Word, PickGuessError> {
+    pick_next_guess_with_params(guess_history, visible_results, n_remaining_words, &ScoringParams::default())
+}
+
+pub fn pick_next_guess_with_params(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize, params: &ScoringParams) -> Result<Word, PickGuessError> {
+    pick_next_guess_until(guess_history, visible_results, n_remaining_words, &[], params, &SearchLimits::default())
+}
+
+/// Like `pick_next_guess_with_params`, but stops scoring guesses once the
+/// deadline in `limits` has passed and returns the best one found so far,
+/// or the possible answer which most is known about if none had been scored.
+///
+/// The wordles are scored before the other words, since they are more likely
+/// to be good guesses.
+pub fn pick_next_guess_until(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize, solved_words: &[Word], params: &ScoringParams, limits: &SearchLimits) -> Result<Word, PickGuessError> {
+    let only_guesses_solved = solved_words.iter().all(|word| guess_history.contains(word));
+    if *params == ScoringParams::default() && only_guesses_solved {
+        if let Some(guess) = opening_book::lookup(guess_history, visible_results, n_remaining_words) {
+            return Ok(guess)
+        }
+    }
+    search_next_guess(guess_history, visible_results, n_remaining_words, solved_words, params, limits)
+}
+
+fn search_next_guess(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize, solved_words: &[Word], params: &ScoringParams, limits: &SearchLimits) -> Result<Word, PickGuessError> {
+    let candidates = Candidates::for_history(guess_history, visible_results, n_remaining_words, solved_words, params, limits)?;
+    limits.check_cancelled()?;
+    if let Some(guess) = candidates.endgame_guess(limits).or_else(|| candidates.known_answer()) {
+        return Ok(guess)
+    }
+
+    let in_time = |_: &Word| limits.in_time() && !limits.is_cancelled();
+    let best_in_time = |guesses: dict::DictIterator<'static>| {
+        guesses.into_par_iter().filter(in_time).map(|guess| (guess, candidates.score(guess, params))).reduce_with(best_of)
     };
-    let invisible_words_bonus = if n_invisible_words >= 5 {
-        let n = n_invisible_words as f64;
-        n / n.log(5.0)
-    } else {
-        n_invisible_words as f64
-    } ;
-
-    if visible_results.iter().any(|x| x.len() != guess_history.len()) {
-        return Err(PyValueError::new_err("Length of histories are different"))
-    }
-
-    let mut possible_invisible_words: Vec<_> =
-        dict::wordles()
-            .into_par_iter()
-            .map(|word| {
-                let mut state = ScoringState::for_word(word);
-                state.add_history_items(guess_history);
-                state
-            })
-            .filter(|word| word.current_score() < MAX_SCORE)
-            .collect();
-
-    let possible_visible_words: Vec<_> =
-        visible_results.par_iter().map(|x| {
-            let result_history: &[GuessResult] = x.deref();
-            let possible_words: Vec<_> = {
-                possible_invisible_words.par_iter()
-                    .cloned()
-                    .filter(|state| GuessResult::history_is_possible(guess_history, result_history, state.word))
-                    .collect()
-            };
-            possible_words
-        }).collect();
-
-    if let Some(maximum_invisible_score) = possible_visible_words.iter().map(|possible_words| possible_words.iter().map(|word| word.current_score()).max().unwrap_or(MAX_SCORE)).min() {
-        possible_invisible_words.retain(|word| word.current_score() <= maximum_invisible_score);
-    }
-
-    let possible_visible_words = possible_visible_words;
-    let possible_invisible_words = possible_invisible_words;
-
-    fn average_score(possible_words: &Vec<ScoringState>, extra_guess: Word) -> f64 {
-        let total_score =
-            possible_words.par_iter().map(|state| {
-                let mut state = state.clone();
-                state.add_history_item(extra_guess);
-                state.current_score() as u64
-            }).sum::<u64>();
-        (total_score as f64) / (possible_words.len() as f64)
-    }
-
-    let res =
-        dict::wordles().into_par_iter().chain(dict::other_words().into_par_iter()).map(|guess| {
-            let visible_score =
-                possible_visible_words.iter().map(|possible_words| {
-                    average_score(possible_words, guess)
-                }).sum::<f64>();
-            let invisible_score =
-                average_score(&possible_invisible_words, guess);
-            let score = visible_score + invisible_score * invisible_words_bonus;
-            (guess, score)
-        }).reduce_with(|l, r| if r.1 > l.1 { r } else { l });
-
-    res.map(|word| word.0).ok_or_else(|| {
-        pyo3::exceptions::PyRuntimeError::new_err("Failed to find any words to be possible guesses")
-    })
+    let res = match (best_in_time(dict::wordles()), best_in_time(dict::other_words())) {
+        (Some(l), Some(r)) => Some(best_of(l, r)),
+        (l, r) => l.or(r),
+    };
+    limits.check_cancelled()?;
+    candidates.or_best_known_word(res.map(|word| word.0), limits)
 }
 
-impl GuessResult {
-    fn from_str_for_py(s: &str) -> PyResult<Self> {
-        let s = s.as_bytes();
-        if s.len() != WORD_LENGTH {
-            return Err(pyo3::exceptions::PyValueError::new_err(format!("Wrong length: guess result must be exactly {} characters", WORD_LENGTH)));
+/// Picks `n_guesses` guesses to make one after another without reading their
+/// results in between.
+///
+/// Answers which are already known are entered first. Each other guess is
+/// chosen as if the ones before it had been made, so together they tend to
+/// cover different letters.
+pub fn pick_next_guesses_inner(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize, solved_words: &[Word], params: &ScoringParams, n_guesses: usize) -> Result<Vec<Word>, PickGuessError> {
+    let mut candidates = Candidates::for_history(guess_history, visible_results, n_remaining_words, solved_words, params, &SearchLimits::default())?;
+    candidates.pick_unseen_guesses(params, n_guesses)
+}
+
+/// The score of every guess, in the order of `guess_pool_inner`.
+///
+/// These are the scores `pick_next_guess_with_params` chooses the best of,
+/// without the opening book or the exact search near the end of a game.
+pub fn score_all_guesses_inner(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize, solved_words: &[Word], params: &ScoringParams) -> Result<Vec<f64>, PickGuessError> {
+    let candidates = Candidates::for_history(guess_history, visible_results, n_remaining_words, solved_words, params, &SearchLimits::default())?;
+    Ok(all_guesses().map(|guess| candidates.score(guess, params)).collect())
+}
+
+/// Every word which can be guessed, wordles first.
+pub fn guess_pool_inner() -> Vec<Word> {
+    dict::wordles().chain(dict::other_words()).collect()
+}
+
+/// Picks a guess with the search chosen from Python, the browser or the server.
+#[cfg(any(feature = "python", feature = "wasm", feature = "serde"))]
+fn solve(guess_history: &[Word], result_histories: &[Vec<GuessResult>], n_remaining_words: usize, solved_words: &[Word], lookahead: Option<usize>, limits: &SearchLimits) -> Result<Word, PickGuessError> {
+    match lookahead {
+        None => pick_next_guess_until(guess_history, result_histories, n_remaining_words, solved_words, &ScoringParams::default(), limits),
+        Some(top_k) => lookahead::pick_next_guess_lookahead(guess_history, result_histories, n_remaining_words, solved_words, &ScoringParams::default(), top_k, limits),
+    }
+}
+
+impl LetterMatch {
+    /// The character standing for this match in a result string.
+    fn to_char(self) -> char {
+        match self {
+            LetterMatch::Nothing => ' ',
+            LetterMatch::Partial => 'o',
+            LetterMatch::Exact => 'O',
+            LetterMatch::Unknown => '?',
+        }
+    }
+}
+
+impl TryFrom<char> for LetterMatch {
+    type Error = GuessResultOfStringError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            ' ' => Ok(LetterMatch::Nothing),
+            'o' => Ok(LetterMatch::Partial),
+            'O' => Ok(LetterMatch::Exact),
+            '?' => Ok(LetterMatch::Unknown),
+            _ => Err(GuessResultOfStringError::InvalidCharacter),
+        }
+    }
+}
+
+/// Writes the single character used for the match in a result string.
+impl Display for LetterMatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl FromStr for LetterMatch {
+    type Err = GuessResultOfStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => LetterMatch::try_from(c),
+            _ => Err(GuessResultOfStringError::InvalidCharacter),
+        }
+    }
+}
+
+/// Writes the result as five characters, which are each ' ' for no match, 'o'
+/// for a partial match, 'O' for an exact match or '?' if not known.
+impl Display for GuessResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|r| write!(f, "{}", r.to_char()))
+    }
+}
+
+impl FromStr for GuessResult {
+    type Err = GuessResultOfStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.chars().count() != WORD_LENGTH {
+            return Err(GuessResultOfStringError::WrongLength);
         }
         let mut res = [LetterMatch::Nothing; WORD_LENGTH];
-        for (res, &b) in res.iter_mut().zip(s.iter()) {
-            if b == b' ' { continue;
-            } else if b == b'o' {
-                *res = LetterMatch::Partial;
-            } else if b == b'O' {
-                *res = LetterMatch::Exact;
-            } else {
-                return Err(pyo3::exceptions::PyValueError::new_err("Invalid character: guess result must be five characters which are all either ' ' for no match, 'o' for partial match or 'O' for exact match"));
-            }
+        for (res, c) in res.iter_mut().zip(s.chars()) {
+            *res = LetterMatch::try_from(c)?;
         }
         Ok(GuessResult(res))
     }
 }
 
-impl<'source> FromPyObject<'source> for GuessResult {
-    fn extract(ob: &'source PyAny) -> PyResult<Self> {
-        GuessResult::from_str_for_py(<&str as FromPyObject>::extract(ob)?)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GuessResultOfStringError {
+    WrongLength,
+    InvalidCharacter,
+}
+
+impl Display for GuessResultOfStringError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuessResultOfStringError::WrongLength => write!(f, "Wrong length: guess result must be exactly {} characters", WORD_LENGTH),
+            GuessResultOfStringError::InvalidCharacter => write!(f, "Invalid character: guess result must be five characters which are all either ' ' for no match, 'o' for partial match, 'O' for exact match or '?' if not known"),
+        }
+    }
+}
+
+impl Error for GuessResultOfStringError {
+
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for GuessResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for GuessResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let result = String::deserialize(deserializer)?;
+        result.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for LetterMatch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for LetterMatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let letter_match = String::deserialize(deserializer)?;
+        letter_match.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -261,7 +727,7 @@ mod tests {
     }
 
     fn result(str: &str) -> GuessResult {
-        GuessResult::from_str_for_py(str).unwrap()
+        str.parse().unwrap()
     }
 
     #[test]
@@ -278,6 +744,181 @@ mod tests {
         assert_eq!(possible("    o", "aabee", "hello"), true);
     }
 
+    #[test]
+    fn test_unknown_tiles_match_any_outcome() {
+        fn possible(r: &str, guess: &str, the_word: &str) -> bool {
+            result(r).is_possible(word(guess), word(the_word))
+        }
+        assert!(possible(" ?   ", "deair", "hello"));
+        assert!(possible(" ?   ", "deair", "stoln"));
+        assert!(possible("?????", "deair", "hello"));
+        assert!(!possible("?   ?", "deair", "hello"));
+        assert!(possible("  o? ", "stoln", "hello"));
+        assert!(!possible("  O? ", "stoln", "hello"));
+        assert!(possible("?   o", "aabee", "hello"));
+    }
+
+    #[test]
+    fn test_only_known_results_are_indexed() {
+        assert_eq!(result("     ").index(), Some(0));
+        assert_eq!(result("OOOOO").index(), Some(N_GUESS_RESULTS - 1));
+        assert_eq!(result("  O? ").index(), None);
+    }
+
+    #[test]
+    fn test_result_string_round_trip() {
+        for s in ["     ", "  oO ", "OOOOO", "o O o", "?o O?"] {
+            assert_eq!(result(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_letter_match_round_trip() {
+        for r in [LetterMatch::Nothing, LetterMatch::Partial, LetterMatch::Exact, LetterMatch::Unknown] {
+            assert_eq!(r.to_string().parse::<LetterMatch>(), Ok(r));
+        }
+        assert_eq!("oo".parse::<LetterMatch>(), Err(GuessResultOfStringError::InvalidCharacter));
+        assert_eq!("  é  ".parse::<GuessResult>(), Err(GuessResultOfStringError::InvalidCharacter));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        for r in [LetterMatch::Nothing, LetterMatch::Partial, LetterMatch::Exact, LetterMatch::Unknown] {
+            assert_eq!(serde_json::from_str::<LetterMatch>(&serde_json::to_string(&r).unwrap()).unwrap(), r);
+        }
+        assert_eq!(serde_json::to_string(&result("o O ?")).unwrap(), "\"o O ?\"");
+    }
+
+    #[test]
+    fn test_evaluate_examples() {
+        fn evaluate(guess: &str, the_word: &str) -> GuessResult {
+            GuessResult::evaluate(word(guess), word(the_word))
+        }
+        assert_eq!(evaluate("deair", "stoln"), result("     "));
+        assert_eq!(evaluate("stoln", "hello"), result("  oO "));
+        assert_eq!(evaluate("aabee", "hello"), result("   o "));
+        assert_eq!(evaluate("llama", "hello"), result("oo   "));
+        assert_eq!(evaluate("hello", "hello"), result("OOOOO"));
+        assert!(dict::wordles().take(50).all(|guess| {
+            dict::wordles().all(|the_word| evaluate_is_possible(guess, the_word))
+        }));
+
+        fn evaluate_is_possible(guess: Word, the_word: Word) -> bool {
+            GuessResult::evaluate(guess, the_word).is_possible(guess, the_word)
+        }
+    }
+
+    #[test]
+    fn test_candidates_exclude_guessed_and_solved_words() {
+        // Every letter of "crone" is known in place, but it has not been guessed.
+        let guess_history = [word("crony"), word("soare")];
+        let results = |the_word: &str| guess_history.iter().map(|&guess| GuessResult::evaluate(guess, word(the_word))).collect::<Vec<_>>();
+        let visible_results = [results("crone")];
+        let possible_words = |solved_words: &[Word]| -> Vec<Word> {
+            let candidates = Candidates::for_history(&guess_history, &visible_results, 2, solved_words, &ScoringParams::default(), &SearchLimits::default()).unwrap();
+            assert!(candidates.invisible.iter().all(|state| !guess_history.contains(&state.word)));
+            candidates.visible[0].iter().map(|state| state.word).collect()
+        };
+        assert_eq!(possible_words(&[]), vec![word("crone")]);
+        assert_eq!(possible_words(&[word("crone")]), vec![]);
+    }
+
+    #[test]
+    fn test_candidates_agree_with_board() {
+        let guess_history = [word("crony"), word("soare")];
+        let results = |the_word: &str| guess_history.iter().map(|&guess| GuessResult::evaluate(guess, word(the_word))).collect::<Vec<_>>();
+        let visible_results = [results("crone"), results("snail"), results("cigar")];
+        let solved_words = [word("cigar")];
+        let candidates = Candidates::for_history(&guess_history, &visible_results, 50, &solved_words, &ScoringParams::default(), &SearchLimits::default()).unwrap();
+        for (possible_words, result_history) in candidates.visible.iter().zip(&visible_results) {
+            let words: Vec<Word> = possible_words.iter().map(|state| state.word).collect();
+            assert_eq!(board::possible_words(&guess_history, result_history, &solved_words), Ok(words));
+        }
+    }
+
+    #[test]
+    fn test_pick_fully_known_unguessed_word() {
+        let guess_history = [word("crony"), word("soare")];
+        let visible_results = [guess_history.iter().map(|&guess| GuessResult::evaluate(guess, word("crone"))).collect::<Vec<_>>()];
+        for n_remaining_words in [1, 2, 50] {
+            assert_eq!(pick_next_guess_inner(&guess_history, &visible_results, n_remaining_words), Ok(word("crone")));
+        }
+    }
+
+    #[test]
+    fn test_search_past_deadline() {
+        let visible_results: &[Vec<GuessResult>] = &[];
+        let limits = SearchLimits { deadline: Some(Instant::now()), cancelled: None };
+        let res = search_next_guess(&[], visible_results, 1000, &[], &ScoringParams::default(), &limits);
+        assert_eq!(res, Err(PickGuessError::OutOfTime));
+    }
+
+    #[test]
+    fn test_fall_back_to_best_known_word() {
+        let state = |the_word: &str| {
+            let mut state = ScoringState::for_word(word(the_word));
+            state.add_history_item(word("crone"));
+            state
+        };
+        let candidates = Candidates {
+            visible: vec![vec![state("hello"), state("crony")]],
+            invisible: vec![state("chore")],
+            n_invisible_words: 1,
+            invisible_words_bonus: 1.0,
+        };
+        let past_deadline = SearchLimits { deadline: Some(Instant::now()), cancelled: None };
+        assert_eq!(candidates.or_best_known_word(None, &past_deadline), Ok(word("crony")));
+        assert_eq!(candidates.or_best_known_word(Some(word("soare")), &past_deadline), Ok(word("soare")));
+        assert_eq!(candidates.or_best_known_word(None, &SearchLimits::default()), Err(PickGuessError::NoPossibleGuesses));
+    }
+
+    #[test]
+    fn test_search_cancelled() {
+        let visible_results: &[Vec<GuessResult>] = &[];
+        let limits = SearchLimits { deadline: None, cancelled: Some(Arc::new(AtomicBool::new(true))) };
+        let res = search_next_guess(&[], visible_results, 1000, &[], &ScoringParams::default(), &limits);
+        assert_eq!(res, Err(PickGuessError::Cancelled));
+    }
+
+    #[test]
+    fn test_score_all_guesses_matches_pool() {
+        let guess_history = [word("soare")];
+        let visible_results = [vec![result("  o o")]];
+        let scores = score_all_guesses_inner(&guess_history, &visible_results, 1000, &[], &ScoringParams::default()).unwrap();
+        let pool = guess_pool_inner();
+        assert_eq!(scores.len(), pool.len());
+        let candidates = Candidates::for_history(&guess_history, &visible_results, 1000, &[], &ScoringParams::default(), &SearchLimits::default()).unwrap();
+        for i in [0, pool.len() / 2, pool.len() - 1] {
+            assert_eq!(scores[i], candidates.score(pool[i], &ScoringParams::default()));
+        }
+    }
+
+    #[test]
+    fn test_unseen_guesses_cover_different_letters() {
+        let mut candidates = Candidates {
+            visible: vec![],
+            invisible: ["crane", "slate", "moist", "pudgy", "hello", "world"].iter().map(|&w| ScoringState::for_word(word(w))).collect(),
+            n_invisible_words: 6,
+            invisible_words_bonus: 1.0,
+        };
+        let guesses = candidates.pick_unseen_guesses(&ScoringParams::default(), 2).unwrap();
+        assert_eq!(guesses.len(), 2);
+        assert!(!guesses[1].bytes().iter().any(|letter| guesses[0].bytes().contains(letter)));
+    }
+
+    #[test]
+    fn test_unseen_guesses_start_with_known_answers() {
+        let guess_history = [word("crony"), word("soare")];
+        let results = |the_word: &str| guess_history.iter().map(|&guess| GuessResult::evaluate(guess, word(the_word))).collect::<Vec<_>>();
+        let guesses = pick_next_guesses_inner(&guess_history, &[results("crone")], 1, &[], &ScoringParams::default(), 3).unwrap();
+        assert_eq!(guesses.len(), 3);
+        assert_eq!(guesses[0], word("crone"));
+        assert!(!guesses[1..].contains(&word("crone")));
+        let guesses = pick_next_guesses_inner(&guess_history, &[results("crone")], 50, &[], &ScoringParams::default(), 1).unwrap();
+        assert_eq!(guesses, vec![word("crone")]);
+    }
+
     // #[test]
     // fn test_pick_next_guess_start() {
     //     let visible_results: &[Vec<GuessResult>] = &[];
@@ -320,20 +961,3 @@ mod tests {
     //     assert_eq!(pick_next_guess_inner(&[word("cigar")], visible_results, 1000).unwrap(), word("cigar"))
     // }
 }
-
-
-
-/// Finds a next guess that can be made in a game of kilordle.
-#[pyfunction]
-fn pick_next_guess(guess_history: Vec<Word>, result_histories: Vec<Vec<GuessResult>>, n_remaining_words: usize) -> PyResult<String> {
-    let next_guess = pick_next_guess_inner(guess_history.as_slice(), result_histories.as_slice(), n_remaining_words)?;
-    String::from_utf8(next_guess.bytes().as_slice().to_owned())
-        .map_err(|_| pyo3::exceptions::PyRuntimeError::new_err("Somehow got invalid characters in a word"))
-}
-
-/// A Python module implemented in Rust.
-#[pymodule]
-fn kilordle_droid(_py: Python, m: &PyModule) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(pick_next_guess, m)?)?;
-    Ok(())
-}
@@ -0,0 +1,183 @@
+//! Two-ply search: compares the best few guesses by what each result they
+//! could show would leave on the boards on screen.
+
+use std::collections::HashMap;
+use rayon::prelude::*;
+use crate::{all_guesses, average_score, board, guess_pool_inner, Candidates, GuessResult, PickGuessError, ScoringParams, ScoringState, SearchLimits, Word};
+
+/// A reasonable number of first guesses to look ahead from.
+pub const DEFAULT_TOP_K: usize = 8;
+
+/// Picks a guess by looking two turns ahead.
+///
+/// The `top_k` best guesses by the usual score are each scored again by the
+/// expected score after that guess and then the best follow-up. Each board on
+/// screen is split by the result the guess would show on it, and each of
+/// those results is weighed by how many of the board's possible words would
+/// show it. One follow-up has to be chosen for all the boards, so it is scored
+/// on every board, with the others left as they are after the guess, and the
+/// expectations found from each board's results are averaged.
+///
+/// The words not on screen show no results, so their part of the score is
+/// theirs after both guesses too, and the two turns are weighed against each
+/// other the same way on and off screen.
+///
+/// As in the usual search, the exact search is used near the end of a game
+/// and a board's answer is guessed once it is known, since looking ahead
+/// cannot tell the guesses apart then.
+///
+/// Once the deadline in `limits` has passed, only the first guesses looked
+/// ahead from by then are compared, or the usual score decides if there were
+/// none.
+pub fn pick_next_guess_lookahead(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize, solved_words: &[Word], params: &ScoringParams, top_k: usize, limits: &SearchLimits) -> Result<Word, PickGuessError> {
+    let mut candidates = Candidates::for_history(guess_history, visible_results, n_remaining_words, solved_words, params, limits)?;
+    candidates.visible.retain(|possible_words| !possible_words.is_empty());
+    limits.check_cancelled()?;
+    if let Some(guess) = candidates.endgame_guess(limits).or_else(|| candidates.known_answer()) {
+        return Ok(guess)
+    }
+
+    let in_time = |_: &Word| limits.in_time() && !limits.is_cancelled();
+    let mut first_guesses: Vec<(Word, f64)> =
+        all_guesses().filter(in_time).map(|guess| (guess, candidates.score(guess, params))).collect();
+    limits.check_cancelled()?;
+    first_guesses.sort_by(|l, r| r.1.partial_cmp(&l.1).unwrap_or(std::cmp::Ordering::Equal));
+    first_guesses.truncate(top_k.max(1));
+
+    let follow_ups = guess_pool_inner();
+    let mut scored = Vec::with_capacity(first_guesses.len());
+    for &(guess, _) in &first_guesses {
+        let score = two_turn_score(&candidates, guess, &follow_ups, params, limits);
+        // A guess cut short by the deadline is left out rather than compared
+        // with ones which were not.
+        if !limits.in_time() || limits.is_cancelled() {
+            break
+        }
+        scored.push((guess, score));
+    }
+    limits.check_cancelled()?;
+
+    let res = scored.into_iter().reduce(|l, r| if r.1 > l.1 { r } else { l }).map(|(guess, _)| guess);
+    candidates.or_best_known_word(res.or(first_guesses.first().map(|&(guess, _)| guess)), limits)
+}
+
+/// The expected score after `guess` and then the best of `follow_ups`.
+fn two_turn_score(candidates: &Candidates, guess: Word, follow_ups: &[Word], params: &ScoringParams, limits: &SearchLimits) -> f64 {
+    let in_time = || limits.in_time() && !limits.is_cancelled();
+    let invisible = Candidates {
+        visible: Vec::new(),
+        invisible: after_guess(candidates.invisible.iter(), guess),
+        ..*candidates
+    };
+    let boards: Vec<Vec<ScoringState>> = candidates.visible.iter().map(|possible_words| after_guess(possible_words.iter(), guess)).collect();
+
+    // The score of each follow-up on each board and on the words not on
+    // screen, before knowing the result of `guess`.
+    let board_scores: Vec<Vec<f64>> = boards.iter().map(|states| {
+        follow_ups.par_iter().map(|&follow_up| if in_time() { params.visible_weight * average_score(states, follow_up, params) } else { 0.0 }).collect()
+    }).collect();
+    let totals: Vec<f64> = follow_ups.par_iter().enumerate().map(|(i, &follow_up)| {
+        if !in_time() {
+            return 0.0
+        }
+        invisible.invisible_score(follow_up, params) + board_scores.iter().map(|scores| scores[i]).sum::<f64>()
+    }).collect();
+
+    if boards.is_empty() {
+        return totals.into_iter().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    let expected_per_board = boards.iter().zip(&board_scores).map(|(states, scores)| {
+        let by_word: HashMap<Word, &ScoringState> = states.iter().map(|state| (state.word, state)).collect();
+        let words: Vec<Word> = states.iter().map(|state| state.word).collect();
+        board::partition(guess, &words).into_iter().map(|(_, part)| {
+            let part_states: Vec<ScoringState> = part.iter().map(|word| by_word[word].clone()).collect();
+            // The follow-up is chosen knowing this board's result, in place of
+            // its score over all of the board's words.
+            let best = follow_ups.par_iter().enumerate().map(|(i, &follow_up)| {
+                if !in_time() {
+                    return f64::NEG_INFINITY
+                }
+                totals[i] - scores[i] + params.visible_weight * average_score(&part_states, follow_up, params)
+            }).reduce(|| f64::NEG_INFINITY, f64::max);
+            best * part.len() as f64 / words.len() as f64
+        }).sum::<f64>()
+    });
+    expected_per_board.sum::<f64>() / boards.len() as f64
+}
+
+fn after_guess<'a>(states: impl Iterator<Item=&'a ScoringState>, guess: Word) -> Vec<ScoringState> {
+    states.map(|state| {
+        let mut state = state.clone();
+        state.add_history_item(guess);
+        state
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(s: &str) -> Word {
+        s.try_into().unwrap()
+    }
+
+    fn states(words: &[&str]) -> Vec<ScoringState> {
+        words.iter().map(|&it| ScoringState::for_word(word(it))).collect()
+    }
+
+    fn candidates(visible: &[&[&str]], invisible: &[&str]) -> Candidates {
+        Candidates {
+            visible: visible.iter().map(|words| states(words)).collect(),
+            invisible: states(invisible),
+            n_invisible_words: 0,
+            invisible_words_bonus: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_separating_guess_leaves_board_solvable() {
+        let params = ScoringParams::default();
+        let limits = SearchLimits::default();
+        let candidates = candidates(&[&["hello", "jello", "cello"]], &["xylyl"]);
+        let score = |guess: Word| two_turn_score(&candidates, guess, &guess_pool_inner(), &params, &limits);
+        let separating = score(word("chjay"));
+        let not_separating = score(word("xylyl"));
+        // Whichever the answer is, it is then fully known after the follow-up.
+        assert_eq!(separating, 15.0);
+        assert!(not_separating < separating);
+    }
+
+    #[test]
+    fn test_pick_fully_known_unguessed_word() {
+        let guess_history = [word("crony"), word("soare")];
+        let visible_results = [guess_history.iter().map(|&guess| GuessResult::evaluate(guess, word("crone"))).collect::<Vec<_>>()];
+        for n_remaining_words in [1, 50] {
+            let guess = pick_next_guess_lookahead(&guess_history, &visible_results, n_remaining_words, &[], &ScoringParams::default(), 4, &SearchLimits::default());
+            assert_eq!(guess, Ok(word("crone")));
+        }
+    }
+
+    #[test]
+    fn test_results_weighed_by_number_of_words() {
+        let params = ScoringParams::default();
+        let limits = SearchLimits::default();
+        let candidates = candidates(&[&["hello", "jello", "cello"]], &[]);
+        // "hzzzz" shows "hello" apart from the other two, after which "hello"
+        // is fully known, and "jello" as the follow-up leaves 15 or 12 points.
+        let score = two_turn_score(&candidates, word("hzzzz"), &[word("hello"), word("jello")], &params, &limits);
+        assert_eq!(score, (15.0 + 2.0 * (15.0 + 12.0) / 2.0) / 3.0);
+    }
+
+    #[test]
+    fn test_follow_up_is_shared_between_boards() {
+        let params = ScoringParams::default();
+        let limits = SearchLimits::default();
+        // Once "hello" and "world" are known, one follow-up can only fill in
+        // one of them, so the score is not that of knowing both.
+        let candidates = candidates(&[&["hello", "jello"], &["world", "would"]], &["xylyl"]);
+        let guess = word("hjrdx");
+        let score = two_turn_score(&candidates, guess, &guess_pool_inner(), &params, &limits);
+        assert!(score < 2.0 * 15.0);
+    }
+}
@@ -0,0 +1,63 @@
+//! Guesses for the start of a game, worked out ahead of time by the
+//! `opening_book` binary since every game starts from the same state.
+//!
+//! The book follows on from each of its guesses with no boards on screen, so
+//! after the first guess it only applies while none are being read.
+
+use crate::{search_next_guess, GuessResult, PickGuessError, ScoringParams, SearchLimits, Word, WORD_LENGTH};
+
+/// The number of words left at the start of a game, which the book is for.
+pub const N_STARTING_WORDS: usize = 1000;
+
+const OPENING_BOOK: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/opening_book.bin"));
+
+fn book() -> impl Iterator<Item=Word> {
+    OPENING_BOOK.chunks_exact(WORD_LENGTH).map(|word| {
+        Word::from(<[u8; WORD_LENGTH]>::try_from(word).expect("Chunks are the right length"))
+    })
+}
+
+/// The book's next guess, if the game so far has followed it.
+///
+/// With no guesses made, boards on screen are allowed as long as they have no
+/// results, as they are scored the same as the words not on screen then.
+pub fn lookup(guess_history: &[Word], visible_results: &[Vec<GuessResult>], n_remaining_words: usize) -> Option<Word> {
+    let follows_book = n_remaining_words == N_STARTING_WORDS
+        && (visible_results.is_empty() || (guess_history.is_empty() && visible_results.iter().all(|results| results.is_empty())));
+    if !follows_book {
+        return None
+    }
+    let mut book = book();
+    if guess_history.iter().all(|&guess| book.next() == Some(guess)) {
+        book.next()
+    } else {
+        None
+    }
+}
+
+/// Works out the first `n_guesses` guesses of the book by searching.
+pub fn generate(n_guesses: usize) -> Result<Vec<Word>, PickGuessError> {
+    let mut guess_history = Vec::with_capacity(n_guesses);
+    for _ in 0..n_guesses {
+        let guess = search_next_guess(&guess_history, &[], N_STARTING_WORDS, &[], &ScoringParams::default(), &SearchLimits::default())?;
+        guess_history.push(guess);
+    }
+    Ok(guess_history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_follows_book() {
+        let book: Vec<Word> = book().collect();
+        assert!(book.len() >= 2);
+        let visible_results: &[Vec<GuessResult>] = &[vec![], vec![]];
+        assert_eq!(lookup(&[], visible_results, N_STARTING_WORDS), Some(book[0]));
+        assert_eq!(lookup(&book[..1], &[], N_STARTING_WORDS), Some(book[1]));
+        assert_eq!(lookup(&book[..1], &[], N_STARTING_WORDS - 1), None);
+        assert_eq!(lookup(&book[1..2], &[], N_STARTING_WORDS), None);
+        assert_eq!(lookup(&book, &[], N_STARTING_WORDS), None);
+    }
+}
@@ -0,0 +1,262 @@
+//! Reading the boards from a saved copy of the Kilordle page, by the same rules
+//! as `read_results.js` uses in the browser, so that pages can be kept as
+//! fixtures without running a browser.
+//!
+//! Without a browser there are no computed styles, so the background colour of
+//! a tile is taken from its `style` attribute or else from the last rule in the
+//! page's `<style>` elements which matches it.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use scraper::{ElementRef, Html, Selector};
+use crate::game_state::{Column, GameState, GameStateError};
+use crate::WORD_LENGTH;
+
+const EXACT_COLOUR: (u8, u8, u8) = (34, 238, 51);
+const PARTIAL_COLOUR: (u8, u8, u8) = (238, 222, 35);
+
+/// What was read from a saved page.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PageSnapshot {
+    /// Every board on screen, leaving out the columns showing "+".
+    pub columns: Vec<Column>,
+    /// The number in the "Remaining: N/1000" counter.
+    pub n_remaining_words: usize,
+}
+
+impl PageSnapshot {
+    /// Checks the columns read, as `GameState::from_columns` does.
+    pub fn game_state(&self) -> Result<GameState, GameStateError> {
+        GameState::from_columns(&self.columns)
+    }
+}
+
+/// Why the boards could not be read from a page, with columns and rows
+/// counted from zero.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PageSnapshotError {
+    NavbarNotFound { n_found: usize },
+    ResultsNotFound,
+    RemainingNotFound,
+    NotADiv { column: usize },
+    NoRows { column: usize },
+    LastRowNotEmpty { column: usize },
+    WrongRowLength { column: usize, row: usize, n_tiles: usize },
+}
+
+impl Display for PageSnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageSnapshotError::NavbarNotFound { n_found } =>
+                write!(f, "Expected one navbar showing \"Kilordle\" and \"Remaining:\", but found {}", n_found),
+            PageSnapshotError::ResultsNotFound => write!(f, "Navbar is not followed by the results"),
+            PageSnapshotError::RemainingNotFound => write!(f, "Navbar does not show \"Remaining: N/1000\""),
+            PageSnapshotError::NotADiv { column } => write!(f, "Column {} has a child which is not a div", column),
+            PageSnapshotError::NoRows { column } => write!(f, "Column {} does not have any rows", column),
+            PageSnapshotError::LastRowNotEmpty { column } => write!(f, "Last row of column {} is not empty", column),
+            PageSnapshotError::WrongRowLength { column, row, n_tiles } =>
+                write!(f, "Column {} row {} has {} tiles rather than {}", column, row, n_tiles, WORD_LENGTH),
+        }
+    }
+}
+
+impl Error for PageSnapshotError {
+
+}
+
+fn text(elem: ElementRef) -> String {
+    elem.text().collect()
+}
+
+/// Parses a CSS colour written as `rgb(r, g, b)`, `rgba(r, g, b, a)`,
+/// `#rrggbb` or `#rgb`.
+fn parse_colour(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+        let pair = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return match hex.len() {
+            3 => Some((digit(0)? * 17, digit(1)? * 17, digit(2)? * 17)),
+            6 => Some((pair(0)?, pair(2)?, pair(4)?)),
+            _ => None,
+        }
+    }
+    let args = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb("))?.strip_suffix(')')?;
+    let mut parts = args.split(',').map(|part| part.trim().parse::<u8>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+/// The background colour set by a list of declarations such as a `style`
+/// attribute, if any.
+fn background_colour(declarations: &str) -> Option<(u8, u8, u8)> {
+    declarations.split(';').rev().find_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+        let value = value.trim().trim_end_matches("!important").trim();
+        match property.trim() {
+            "background-color" => parse_colour(value),
+            "background" => shorthand_colour(value),
+            _ => None,
+        }
+    })
+}
+
+/// The colour anywhere in a `background` shorthand, where `rgb(...)` may
+/// contain spaces.
+fn shorthand_colour(value: &str) -> Option<(u8, u8, u8)> {
+    if let Some(start) = value.find("rgb") {
+        let end = start + value[start..].find(')')?;
+        return parse_colour(&value[start..=end])
+    }
+    value.split_whitespace().find_map(parse_colour)
+}
+
+/// The rules in a stylesheet which set a background colour, in order. Rules
+/// inside at-rules such as `@media` are included.
+fn background_rules(css: &str, rules: &mut Vec<(Selector, (u8, u8, u8))>) {
+    let mut rest = css;
+    while let Some(open) = rest.find('{') {
+        let prelude = rest[..open].trim();
+        let mut depth = 0;
+        let close = rest[open..].char_indices().find_map(|(i, c)| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {},
+            }
+            (depth == 0).then_some(open + i)
+        }).unwrap_or(rest.len());
+        let body = &rest[(open + 1).min(close)..close];
+        if prelude.starts_with('@') {
+            background_rules(body, rules);
+        } else if let (Some(colour), Ok(selector)) = (background_colour(body), Selector::parse(prelude)) {
+            rules.push((selector, colour));
+        }
+        rest = &rest[(close + 1).min(rest.len())..];
+    }
+}
+
+/// Reads the boards and the number of remaining words from the HTML of a
+/// saved Kilordle page.
+pub fn read_page_snapshot(html: &str) -> Result<PageSnapshot, PageSnapshotError> {
+    let document = Html::parse_document(html);
+
+    let navbar_candidates: Vec<ElementRef> = document.select(&Selector::parse("div.App div").unwrap())
+        .filter(|elem| {
+            let text = text(*elem);
+            text.contains("Kilordle") && text.contains("Remaining:")
+        })
+        .collect();
+    let navbars: Vec<ElementRef> = navbar_candidates.iter().copied()
+        .filter(|elem| !navbar_candidates.iter().any(|other| other.ancestors().any(|it| it.id() == elem.id())))
+        .collect();
+    let navbar = match navbars.as_slice() {
+        [navbar] => *navbar,
+        _ => return Err(PageSnapshotError::NavbarNotFound { n_found: navbars.len() }),
+    };
+    let results = navbar.next_siblings().find_map(ElementRef::wrap).ok_or(PageSnapshotError::ResultsNotFound)?;
+
+    let navbar_text = text(navbar);
+    let remaining = navbar_text.split_once("Remaining:").ok_or(PageSnapshotError::RemainingNotFound)?.1.trim_start();
+    let n_remaining_words = remaining.split_once("/1000")
+        .and_then(|(n, _)| n.trim().parse().ok())
+        .ok_or(PageSnapshotError::RemainingNotFound)?;
+
+    let mut rules = Vec::new();
+    for style in document.select(&Selector::parse("style").unwrap()) {
+        background_rules(&text(style), &mut rules);
+    }
+    let colour = |elem: ElementRef| {
+        elem.attr("style").and_then(background_colour)
+            .or_else(|| rules.iter().rev().find(|(selector, _)| selector.matches(&elem)).map(|(_, colour)| *colour))
+    };
+    let result_char = |colour: Option<(u8, u8, u8)>| match colour {
+        Some(EXACT_COLOUR) => 'O',
+        Some(PARTIAL_COLOUR) => 'o',
+        _ => ' ',
+    };
+
+    // As in the browser, the colour of the first div with each class stands
+    // for all of them.
+    let mut class_match: HashMap<&str, char> = HashMap::new();
+    for div in results.select(&Selector::parse("div").unwrap()) {
+        let class_name = div.attr("class").unwrap_or("");
+        if !class_match.contains_key(class_name) {
+            class_match.insert(class_name, result_char(colour(div)));
+        }
+    }
+
+    let mut columns = Vec::new();
+    for column_elem in results.child_elements().filter(|elem| !text(*elem).contains('+')) {
+        let column = columns.len();
+        let mut rows: Vec<ElementRef> = column_elem.child_elements().collect();
+        if rows.iter().any(|row| row.value().name() != "div") {
+            return Err(PageSnapshotError::NotADiv { column })
+        }
+        match rows.pop() {
+            None => return Err(PageSnapshotError::NoRows { column }),
+            Some(last) if !text(last).trim().is_empty() => return Err(PageSnapshotError::LastRowNotEmpty { column }),
+            Some(_) => {},
+        }
+
+        let mut guess_history = Vec::new();
+        let mut result_history = Vec::new();
+        for (row, row_elem) in rows.into_iter().enumerate() {
+            let tiles: Vec<ElementRef> = row_elem.child_elements().collect();
+            if tiles.len() != WORD_LENGTH {
+                return Err(PageSnapshotError::WrongRowLength { column, row, n_tiles: tiles.len() })
+            }
+            guess_history.push(tiles.iter().map(|tile| text(*tile)).collect());
+            result_history.push(tiles.iter().map(|tile| {
+                class_match.get(tile.attr("class").unwrap_or("")).copied().unwrap_or_else(|| result_char(colour(*tile)))
+            }).collect());
+        }
+        columns.push(Column { guess_history, result_history });
+    }
+
+    Ok(PageSnapshot { columns, n_remaining_words })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_colour() {
+        assert_eq!(parse_colour("rgb(34, 238, 51)"), Some(EXACT_COLOUR));
+        assert_eq!(parse_colour("rgba(238,222,35,1)"), Some(PARTIAL_COLOUR));
+        assert_eq!(parse_colour("#22EE33"), Some(EXACT_COLOUR));
+        assert_eq!(parse_colour("#fff"), Some((255, 255, 255)));
+        assert_eq!(parse_colour("white"), None);
+        assert_eq!(background_colour("color: black; background: rgb(238, 222, 35) none"), Some(PARTIAL_COLOUR));
+    }
+
+    #[test]
+    fn test_fixture() {
+        let snapshot = read_page_snapshot(include_str!("../tests/fixtures/kilordle_page.html")).unwrap();
+        assert_eq!(snapshot.n_remaining_words, 998);
+        let column = |result_history: &[&str]| Column {
+            guess_history: vec!["soare".to_string(), "clint".to_string()],
+            result_history: result_history.iter().map(|s| s.to_string()).collect(),
+        };
+        assert_eq!(snapshot.columns, vec![
+            column(&["  o o", "O    "]),
+            column(&["     ", " OO  "]),
+            column(&["OOOOO", "     "]),
+        ]);
+        assert_eq!(snapshot.game_state().unwrap().result_histories.len(), 3);
+    }
+
+    #[test]
+    fn test_malformed_pages() {
+        assert_eq!(read_page_snapshot("<div class=\"App\"></div>"), Err(PageSnapshotError::NavbarNotFound { n_found: 0 }));
+        let page = |results: &str| format!("<div class=\"App\"><div>Kilordle Remaining: 1000/1000</div><div>{}</div></div>", results);
+        assert_eq!(read_page_snapshot(&page("<div></div>")), Err(PageSnapshotError::NoRows { column: 0 }));
+        assert_eq!(read_page_snapshot(&page("<div><div>s</div></div>")), Err(PageSnapshotError::LastRowNotEmpty { column: 0 }));
+        assert_eq!(
+            read_page_snapshot(&page("<div><div><div>s</div><div>o</div></div><div></div></div>")),
+            Err(PageSnapshotError::WrongRowLength { column: 0, row: 0, n_tiles: 2 }),
+        );
+        assert_eq!(read_page_snapshot(&page("<div>+</div>")).unwrap().columns, vec![]);
+    }
+}
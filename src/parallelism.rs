@@ -0,0 +1,54 @@
+//! Control over which threads the search runs on.
+
+use std::sync::Arc;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// The threads a search runs on: rayon's global pool by default, or a pool
+/// of its own so that several searches side by side do not oversubscribe
+/// the CPU.
+#[derive(Clone, Debug, Default)]
+pub struct Parallelism(Option<Arc<ThreadPool>>);
+
+impl Parallelism {
+    /// Rayon's global thread pool.
+    pub fn global() -> Self {
+        Parallelism(None)
+    }
+
+    /// An existing thread pool, which may be shared with other searches.
+    pub fn pool(pool: Arc<ThreadPool>) -> Self {
+        Parallelism(Some(pool))
+    }
+
+    /// A new thread pool with `n_threads` threads.
+    pub fn threads(n_threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        let pool = ThreadPoolBuilder::new().num_threads(n_threads.max(1)).build()?;
+        Ok(Parallelism::pool(Arc::new(pool)))
+    }
+
+    /// A single thread, so that all of the work is done one piece at a time.
+    pub fn sequential() -> Result<Self, ThreadPoolBuildError> {
+        Parallelism::threads(1)
+    }
+
+    /// Runs `f`, with any parallel work inside it done on these threads.
+    pub fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        match &self.0 {
+            None => f(),
+            Some(pool) => pool.install(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_uses_one_thread() {
+        let parallelism = Parallelism::sequential().unwrap();
+        assert_eq!(parallelism.install(rayon::current_num_threads), 1);
+        let parallelism = Parallelism::threads(3).unwrap();
+        assert_eq!(parallelism.install(rayon::current_num_threads), 3);
+    }
+}
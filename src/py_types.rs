@@ -0,0 +1,147 @@
+//! Python classes for words and guess results, so that Python code can
+//! validate them once and then pass them around instead of plain strings.
+//!
+//! Anywhere the module takes a word or a result, either these classes or
+//! strings are accepted, and they compare equal to the equivalent strings.
+
+// The code pyo3 generates for `__richcmp__` trips this lint on newer compilers.
+#![allow(non_local_definitions)]
+
+use std::cmp::Ordering;
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use crate::{GuessResult, LetterMatch, Word, WORD_LENGTH};
+
+/// A five letter word.
+#[pyclass(name = "Word")]
+#[derive(Copy, Clone)]
+pub struct PyWord(pub Word);
+
+/// A result shown for a guess on one board.
+#[pyclass(name = "GuessResult")]
+#[derive(Copy, Clone)]
+pub struct PyGuessResult(pub GuessResult);
+
+/// The position of `index` into a sequence of `WORD_LENGTH` items, counting
+/// from the end if it is negative like Python does.
+fn position(index: isize) -> PyResult<usize> {
+    let position = if index < 0 { index + WORD_LENGTH as isize } else { index };
+    if (0..WORD_LENGTH as isize).contains(&position) {
+        Ok(position as usize)
+    } else {
+        Err(PyIndexError::new_err("Index out of range"))
+    }
+}
+
+/// The result of comparing with `op`, given how the values compare if
+/// `other` was of the same type.
+fn compare(py: Python, ordering: Option<Ordering>, op: CompareOp) -> PyObject {
+    let ordering = match ordering {
+        Some(ordering) => ordering,
+        None => return py.NotImplemented(),
+    };
+    match op {
+        CompareOp::Eq => ordering.is_eq(),
+        CompareOp::Ne => ordering.is_ne(),
+        CompareOp::Lt => ordering.is_lt(),
+        CompareOp::Le => ordering.is_le(),
+        CompareOp::Gt => ordering.is_gt(),
+        CompareOp::Ge => ordering.is_ge(),
+    }.into_py(py)
+}
+
+#[pymethods]
+impl PyWord {
+    #[new]
+    fn new(word: Word) -> Self {
+        PyWord(word)
+    }
+
+    /// Reads a word from messy text, trimming whitespace and folding case,
+    /// and with `strip_accents` reading accented letters without their
+    /// accents. Raises `ValueError` naming any invalid character.
+    #[staticmethod]
+    #[args(strip_accents = "false")]
+    fn parse(word: &str, strip_accents: bool) -> PyResult<Self> {
+        Ok(PyWord(Word::parse(word, strip_accents)?))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Word('{}')", self.0)
+    }
+
+    fn __richcmp__(&self, py: Python, other: &PyAny, op: CompareOp) -> PyObject {
+        compare(py, other.extract::<Word>().ok().map(|other| self.0.cmp(&other)), op)
+    }
+
+    /// Hashes like the equivalent string, since they compare equal.
+    fn __hash__(&self, py: Python) -> PyResult<isize> {
+        PyString::new(py, &self.0.to_string()).hash()
+    }
+
+    fn __len__(&self) -> usize {
+        WORD_LENGTH
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<char> {
+        Ok(self.0.bytes()[position(index)?] as char)
+    }
+
+    /// The result shown for this word as a guess on the board whose answer is
+    /// `answer`.
+    fn evaluate(&self, answer: Word) -> PyGuessResult {
+        PyGuessResult(GuessResult::evaluate(self.0, answer))
+    }
+}
+
+#[pymethods]
+impl PyGuessResult {
+    /// Takes a result written as five characters, which are each ' ' for no
+    /// match, 'o' for a partial match, 'O' for an exact match or '?' for a
+    /// tile whose colour was not read.
+    #[new]
+    fn new(result: GuessResult) -> Self {
+        PyGuessResult(result)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("GuessResult('{}')", self.0)
+    }
+
+    fn __richcmp__(&self, py: Python, other: &PyAny, op: CompareOp) -> PyObject {
+        match (op, other.extract::<GuessResult>()) {
+            (CompareOp::Eq, Ok(other)) => (self.0 == other).into_py(py),
+            (CompareOp::Ne, Ok(other)) => (self.0 != other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    /// Hashes like the equivalent string, since they compare equal.
+    fn __hash__(&self, py: Python) -> PyResult<isize> {
+        PyString::new(py, &self.0.to_string()).hash()
+    }
+
+    fn __len__(&self) -> usize {
+        WORD_LENGTH
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<LetterMatch> {
+        Ok(self.0.0[position(index)?])
+    }
+
+    /// Whether this result could be shown for `guess` on the board whose
+    /// answer is `word`.
+    fn is_possible(&self, guess: Word, word: Word) -> bool {
+        self.0.is_possible(guess, word)
+    }
+}
@@ -0,0 +1,263 @@
+//! The Python module, which wraps the solver for the driver in `python/`.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+#[cfg(feature = "serde")]
+use crate::game_log;
+use crate::{background, board, game_state, guess_pool_inner, pick_next_guesses_inner, py_types, replay, score_all_guesses_inner, solve};
+use crate::{GuessResult, LetterMatch, Parallelism, PickGuessError, ScoringParams, SearchLimits, Word};
+
+impl From<PickGuessError> for PyErr {
+    fn from(err: PickGuessError) -> Self {
+        match err {
+            PickGuessError::InsufficientRemainingWords | PickGuessError::HistoryLengthMismatch =>
+                PyValueError::new_err(err.to_string()),
+            PickGuessError::NoPossibleGuesses | PickGuessError::Cancelled | PickGuessError::OutOfTime => PyRuntimeError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// The threads to search on, given the number of threads asked for from Python.
+///
+/// The pool for each number of threads is made the first time it is asked for
+/// and kept for later searches, rather than starting new threads every turn.
+pub(crate) fn parallelism_for_py(threads: Option<usize>) -> PyResult<Parallelism> {
+    static POOLS: OnceLock<Mutex<HashMap<usize, Parallelism>>> = OnceLock::new();
+    let n_threads = match threads {
+        None => return Ok(Parallelism::global()),
+        Some(n_threads) => n_threads.max(1),
+    };
+    let mut pools = POOLS.get_or_init(Default::default).lock().expect("Lock should not be poisoned");
+    if let Some(parallelism) = pools.get(&n_threads) {
+        return Ok(parallelism.clone())
+    }
+    let parallelism = Parallelism::threads(n_threads).map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+    pools.insert(n_threads, parallelism.clone());
+    Ok(parallelism)
+}
+
+
+/// Converts a deadline given as seconds since the epoch, like Python's `time.time()`.
+pub(crate) fn deadline_from_timestamp(timestamp: f64) -> PyResult<Instant> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |now| now.as_secs_f64());
+    Ok(Instant::now() + seconds_to_duration(timestamp - now, "deadline")?)
+}
+
+/// Converts a number of seconds from Python, treating negative numbers as zero.
+pub(crate) fn seconds_to_duration(seconds: f64, name: &str) -> PyResult<Duration> {
+    if !seconds.is_finite() {
+        return Err(PyValueError::new_err(format!("{} must be a finite number of seconds, not {}", name, seconds)))
+    }
+    Ok(Duration::from_secs_f64(seconds.clamp(0.0, 1e9)))
+}
+
+impl<'source> FromPyObject<'source> for GuessResult {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(result) = ob.downcast::<PyCell<py_types::PyGuessResult>>() {
+            return Ok(result.borrow().0)
+        }
+        <&str as FromPyObject>::extract(ob)?.parse::<GuessResult>()
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+}
+
+/// Finds a next guess that can be made in a game of kilordle.
+///
+/// If `lookahead` is given, that many of the best guesses are compared by
+/// looking two turns ahead.
+///
+/// If `deadline` is given (as a `time.time()` value), the best guess found by
+/// then is returned.
+///
+/// If `threads` is given, the search runs on that many threads of its own
+/// rather than sharing a global pool, and `threads=1` runs it sequentially.
+///
+/// `solved_words` are words known to have been found already. Words in the
+/// guess history are always treated as no longer possible answers.
+#[pyfunction(lookahead = "None", deadline = "None", threads = "None", solved_words = "Vec::new()")]
+#[allow(clippy::too_many_arguments)]
+fn pick_next_guess(py: Python, guess_history: Vec<Word>, result_histories: Vec<Vec<GuessResult>>, n_remaining_words: usize, lookahead: Option<usize>, deadline: Option<f64>, threads: Option<usize>, solved_words: Vec<Word>) -> PyResult<String> {
+    let limits = SearchLimits { deadline: deadline.map(deadline_from_timestamp).transpose()?, cancelled: None };
+    let parallelism = parallelism_for_py(threads)?;
+    // The search takes a while, so let other Python threads run meanwhile.
+    let next_guess = py.allow_threads(|| parallelism.install(|| {
+        solve(guess_history.as_slice(), result_histories.as_slice(), n_remaining_words, solved_words.as_slice(), lookahead, &limits)
+    }))?;
+    Ok(next_guess.to_string())
+}
+
+/// Finds several guesses to enter one after another before reading any of
+/// their results, for saving trips to read the screen.
+#[pyfunction(threads = "None", solved_words = "Vec::new()")]
+fn pick_next_guesses(py: Python, guess_history: Vec<Word>, result_histories: Vec<Vec<GuessResult>>, n_remaining_words: usize, n_guesses: usize, threads: Option<usize>, solved_words: Vec<Word>) -> PyResult<Vec<String>> {
+    let parallelism = parallelism_for_py(threads)?;
+    let next_guesses = py.allow_threads(|| parallelism.install(|| {
+        pick_next_guesses_inner(guess_history.as_slice(), result_histories.as_slice(), n_remaining_words, solved_words.as_slice(), &ScoringParams::default(), n_guesses)
+    }))?;
+    Ok(next_guesses.iter().map(Word::to_string).collect())
+}
+
+/// Scores every word which can be guessed, as an `array.array` of doubles in
+/// the order of `guess_pool()`, which `numpy.frombuffer` can wrap without
+/// copying.
+#[pyfunction(threads = "None", solved_words = "Vec::new()")]
+fn score_all_guesses(py: Python, guess_history: Vec<Word>, result_histories: Vec<Vec<GuessResult>>, n_remaining_words: usize, threads: Option<usize>, solved_words: Vec<Word>) -> PyResult<PyObject> {
+    let parallelism = parallelism_for_py(threads)?;
+    let scores = py.allow_threads(|| parallelism.install(|| {
+        score_all_guesses_inner(guess_history.as_slice(), result_histories.as_slice(), n_remaining_words, solved_words.as_slice(), &ScoringParams::default())
+    }))?;
+    let bytes: Vec<u8> = scores.iter().flat_map(|score| score.to_ne_bytes()).collect();
+    let array = py.import("array")?.getattr("array")?.call1(("d",))?;
+    array.call_method1("frombytes", (PyBytes::new(py, &bytes),))?;
+    Ok(array.into())
+}
+
+/// Lists every word which can be guessed, in the order `score_all_guesses`
+/// scores them.
+#[pyfunction]
+fn guess_pool() -> Vec<String> {
+    guess_pool_inner().iter().map(Word::to_string).collect()
+}
+
+/// Replays a finished game through the solver.
+///
+/// `result_histories[i]` and `n_remaining_words[i]` are what was on screen
+/// before `guess_history[i]` was played. For each turn, gives a dict with the
+/// guess `played` and its `played_score`, the `solver_guess` and its
+/// `solver_score`, and the `regret` between them.
+#[pyfunction(threads = "None")]
+#[pyo3(name = "replay")]
+fn replay_game(py: Python, guess_history: Vec<Word>, result_histories: Vec<Vec<Vec<GuessResult>>>, n_remaining_words: Vec<usize>, threads: Option<usize>) -> PyResult<Vec<PyObject>> {
+    if result_histories.len() != guess_history.len() || n_remaining_words.len() != guess_history.len() {
+        return Err(PickGuessError::HistoryLengthMismatch.into())
+    }
+    let turns: Vec<_> = guess_history.into_iter().zip(result_histories).zip(n_remaining_words).map(|((guess, visible_results), n_remaining_words)| {
+        replay::GameTurn { visible_results, n_remaining_words, guess }
+    }).collect();
+    let parallelism = parallelism_for_py(threads)?;
+    let analysis = py.allow_threads(|| parallelism.install(|| {
+        replay::replay(&turns, &ScoringParams::default(), &SearchLimits::default())
+    }))?;
+    analysis.into_iter().map(|turn| {
+        let dict = PyDict::new(py);
+        dict.set_item("played", turn.played.to_string())?;
+        dict.set_item("played_score", turn.played_score)?;
+        dict.set_item("solver_guess", turn.solver_guess.to_string())?;
+        dict.set_item("solver_score", turn.solver_score)?;
+        dict.set_item("regret", turn.regret())?;
+        Ok(dict.into())
+    }).collect()
+}
+
+/// Adds a turn to the game log at `path`, creating it if needed.
+///
+/// `result_histories` are the results on screen before `guess` was entered,
+/// and `timestamp` defaults to now.
+#[cfg(feature = "serde")]
+#[pyfunction(solver_guess = "None", solve_seconds = "None", timestamp = "None")]
+#[allow(clippy::too_many_arguments)]
+fn append_game_log(path: &str, guess: Word, result_histories: Vec<Vec<GuessResult>>, n_remaining_words: usize, solver_guess: Option<Word>, solve_seconds: Option<f64>, timestamp: Option<f64>) -> PyResult<()> {
+    let timestamp = timestamp.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |now| now.as_secs_f64()));
+    let record = game_log::TurnRecord {
+        version: game_log::GAME_LOG_VERSION,
+        timestamp,
+        guess,
+        visible_results: result_histories,
+        n_remaining_words,
+        solver_guess,
+        solve_seconds,
+    };
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path).map_err(game_log::GameLogError::Io)?;
+    game_log::write_turn_record(&mut file, &record)?;
+    Ok(())
+}
+
+/// Reads the game log at `path`, as a dict for each turn with the same keys
+/// as the JSON records.
+#[cfg(feature = "serde")]
+#[pyfunction]
+fn read_game_log(py: Python, path: &str) -> PyResult<Vec<PyObject>> {
+    let file = std::fs::File::open(path).map_err(game_log::GameLogError::Io)?;
+    let records = game_log::read_game_log(std::io::BufReader::new(file))?;
+    records.into_iter().map(|record| {
+        let visible_results: Vec<Vec<String>> = record.visible_results.iter()
+            .map(|results| results.iter().map(|result| result.to_string()).collect())
+            .collect();
+        let dict = PyDict::new(py);
+        dict.set_item("version", record.version)?;
+        dict.set_item("timestamp", record.timestamp)?;
+        dict.set_item("guess", record.guess.to_string())?;
+        dict.set_item("visible_results", visible_results)?;
+        dict.set_item("n_remaining_words", record.n_remaining_words)?;
+        dict.set_item("solver_guess", record.solver_guess.map(|guess| guess.to_string()))?;
+        dict.set_item("solve_seconds", record.solve_seconds)?;
+        Ok(dict.into())
+    }).collect()
+}
+
+/// Lists the wordles which could still be the answer on a board, given the
+/// guesses made and the results that board showed for them.
+///
+/// Guessed words and `solved_words` are left out, as they are by the solver.
+#[pyfunction(solved_words = "Vec::new()")]
+fn possible_words(guess_history: Vec<Word>, result_history: Vec<GuessResult>, solved_words: Vec<Word>) -> PyResult<Vec<String>> {
+    Ok(board::possible_words(guess_history.as_slice(), result_history.as_slice(), solved_words.as_slice())?
+        .iter().map(Word::to_string).collect())
+}
+
+/// Counts the words `possible_words` would list.
+#[pyfunction(solved_words = "Vec::new()")]
+fn count_possible_words(guess_history: Vec<Word>, result_history: Vec<GuessResult>, solved_words: Vec<Word>) -> PyResult<usize> {
+    Ok(board::count_possible_words(guess_history.as_slice(), result_history.as_slice(), solved_words.as_slice())?)
+}
+
+/// Groups `candidates` by the result `guess` would show on their board, as
+/// `(result, words, number of words)` with the largest group first.
+#[pyfunction]
+fn partition(guess: Word, candidates: Vec<Word>) -> Vec<(String, Vec<String>, usize)> {
+    board::partition(guess, candidates.as_slice()).into_iter().map(|(result, words)| {
+        (result.to_string(), words.iter().map(Word::to_string).collect(), words.len())
+    }).collect()
+}
+
+/// Checks the columns read from the page by `read_results.js`, each a dict
+/// with `guessHistory` and `resultHistory`, and returns the guess history and
+/// the result history of each column. Raises `ValueError` naming the column
+/// and row of anything wrong.
+#[pyfunction]
+fn game_state_from_columns(columns: Vec<game_state::Column>) -> PyResult<(Vec<String>, Vec<Vec<String>>)> {
+    let state = game_state::GameState::from_columns(&columns)?;
+    let guess_history = state.guess_history.iter().map(Word::to_string).collect();
+    let result_histories = state.result_histories.into_iter()
+        .map(|results| results.iter().map(GuessResult::to_string).collect())
+        .collect();
+    Ok((guess_history, result_histories))
+}
+
+/// A Python module implemented in Rust.
+#[pymodule]
+fn kilordle_droid(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(pick_next_guess, m)?)?;
+    m.add_function(wrap_pyfunction!(pick_next_guesses, m)?)?;
+    m.add_function(wrap_pyfunction!(background::start_solve, m)?)?;
+    m.add_function(wrap_pyfunction!(possible_words, m)?)?;
+    m.add_function(wrap_pyfunction!(count_possible_words, m)?)?;
+    m.add_function(wrap_pyfunction!(partition, m)?)?;
+    m.add_function(wrap_pyfunction!(score_all_guesses, m)?)?;
+    m.add_function(wrap_pyfunction!(guess_pool, m)?)?;
+    m.add_function(wrap_pyfunction!(replay_game, m)?)?;
+    #[cfg(feature = "serde")]
+    m.add_function(wrap_pyfunction!(append_game_log, m)?)?;
+    #[cfg(feature = "serde")]
+    m.add_function(wrap_pyfunction!(read_game_log, m)?)?;
+    m.add_function(wrap_pyfunction!(game_state_from_columns, m)?)?;
+    m.add_class::<background::SolveHandle>()?;
+    m.add_class::<py_types::PyWord>()?;
+    m.add_class::<py_types::PyGuessResult>()?;
+    m.add_class::<LetterMatch>()?;
+    Ok(())
+}
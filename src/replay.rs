@@ -0,0 +1,72 @@
+//! Replays a finished game through the solver, to see on which turns the
+//! guesses played were worse than the solver's by its own scoring.
+
+use crate::{pick_next_guess_until, Candidates, GuessResult, PickGuessError, ScoringParams, SearchLimits, Word};
+
+/// One turn of a recorded game: what was on screen and the guess played.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameTurn {
+    /// The results on each board on screen for the guesses before this turn.
+    pub visible_results: Vec<Vec<GuessResult>>,
+    pub n_remaining_words: usize,
+    pub guess: Word,
+}
+
+/// How the guess played on a turn compares with the solver's choice.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TurnAnalysis {
+    pub played: Word,
+    pub played_score: f64,
+    pub solver_guess: Word,
+    pub solver_score: f64,
+}
+
+impl TurnAnalysis {
+    /// How much higher the solver's guess scored than the one played.
+    ///
+    /// This can be negative when the solver's guess came from the opening
+    /// book or the exact search at the end of a game rather than the score.
+    pub fn regret(&self) -> f64 {
+        self.solver_score - self.played_score
+    }
+}
+
+/// Analyses every turn of a game.
+pub fn replay(turns: &[GameTurn], params: &ScoringParams, limits: &SearchLimits) -> Result<Vec<TurnAnalysis>, PickGuessError> {
+    let mut guess_history = Vec::with_capacity(turns.len());
+    turns.iter().map(|turn| {
+        let candidates = Candidates::for_history(&guess_history, &turn.visible_results, turn.n_remaining_words, &[], params, &SearchLimits::default())?;
+        let solver_guess = pick_next_guess_until(&guess_history, &turn.visible_results, turn.n_remaining_words, &[], params, limits)?;
+        guess_history.push(turn.guess);
+        Ok(TurnAnalysis {
+            played: turn.guess,
+            played_score: candidates.score(turn.guess, params),
+            solver_guess,
+            solver_score: candidates.score(solver_guess, params),
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(s: &str) -> Word {
+        s.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_poor_guess_has_regret() {
+        let answer = word("cigar");
+        let turns = [
+            GameTurn { visible_results: vec![vec![]], n_remaining_words: 1000, guess: word("soare") },
+            GameTurn { visible_results: vec![vec![GuessResult::evaluate(word("soare"), answer)]], n_remaining_words: 1000, guess: word("xylyl") },
+        ];
+        let analysis = replay(&turns, &ScoringParams::default(), &SearchLimits::default()).unwrap();
+        assert_eq!(analysis.len(), 2);
+        // The first guess is the opening book's.
+        assert_eq!(analysis[0].solver_guess, word("soare"));
+        assert_eq!(analysis[0].regret(), 0.0);
+        assert!(analysis[1].regret() > 0.0);
+    }
+}
@@ -0,0 +1,412 @@
+//! Reading the boards from a PNG screenshot of the Kilordle grid, for sessions
+//! where the page itself cannot be read.
+//!
+//! The tiles are found from a `GridLayout` measured from the game, the result
+//! of each tile from its background colour as in `read_results.js`, and its
+//! letter by matching it against `Glyphs` captured from an earlier screenshot
+//! whose guesses are known.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use crate::game_state::Column;
+use crate::{Word, WORD_LENGTH};
+
+const EXACT_COLOUR: [u8; 3] = [34, 238, 51];
+const PARTIAL_COLOUR: [u8; 3] = [238, 222, 35];
+/// How far, as a sum over the channels, a background may be from the colours
+/// above, allowing for scaling and compression.
+const COLOUR_TOLERANCE: u32 = 48;
+/// How far the letter on a tile must be from its background for the tile not
+/// to be blank.
+const INK_THRESHOLD: u32 = 192;
+/// The smallest overlap between a letter and its best glyph to be accepted.
+const MIN_GLYPH_MATCH: f64 = 0.8;
+
+/// An image decoded from a PNG, as 8-bit RGB.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// Decodes a PNG, dropping any alpha channel.
+    pub fn from_png(reader: impl Read) -> Result<Self, ScreenshotError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|err| ScreenshotError::Decode(err.to_string()))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|err| ScreenshotError::Decode(err.to_string()))?;
+        let channels = info.color_type.samples();
+        let pixels = buffer[..info.buffer_size()].chunks(info.line_size)
+            .flat_map(|line| line.chunks(channels).take(info.width as usize))
+            .map(|pixel| match pixel {
+                [grey] | [grey, _] => [*grey; 3],
+                [r, g, b, ..] => [*r, *g, *b],
+                _ => unreachable!("PNG pixels have 1 to 4 channels"),
+            })
+            .collect();
+        Ok(Image { width: info.width, height: info.height, pixels })
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        self.pixels[y as usize * self.width as usize + x as usize]
+    }
+}
+
+/// Where the tiles are in a screenshot, in pixels.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GridLayout {
+    /// The top left corner of the first tile of each board.
+    pub columns: Vec<(u32, u32)>,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// The distance from one tile to the next in a row.
+    pub tile_pitch_x: u32,
+    /// The distance from one row to the next.
+    pub tile_pitch_y: u32,
+}
+
+/// The letters of a tile, as the pixels which differ from its background.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Mask {
+    width: u32,
+    height: u32,
+    ink: Vec<bool>,
+}
+
+impl Mask {
+    fn n_ink(&self) -> usize {
+        self.ink.iter().filter(|&&ink| ink).count()
+    }
+
+    /// The overlap of the ink in two masks, scaling `other` to the size of
+    /// `self`, from 0 for none to 1 for the same.
+    fn overlap(&self, other: &Mask) -> f64 {
+        let (mut both, mut either) = (0, 0);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let theirs = other.ink[((y * other.height / self.height) * other.width + x * other.width / self.width) as usize];
+                let ours = self.ink[(y * self.width + x) as usize];
+                both += (ours && theirs) as u32;
+                either += (ours || theirs) as u32;
+            }
+        }
+        if either == 0 { 1.0 } else { both as f64 / either as f64 }
+    }
+}
+
+/// A tile cut out of a screenshot.
+struct Tile {
+    background: [u8; 3],
+    mask: Mask,
+}
+
+fn colour_distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter().zip(b.iter()).map(|(&a, &b)| (a as i32 - b as i32).unsigned_abs()).sum()
+}
+
+impl Tile {
+    fn read(image: &Image, x0: u32, y0: u32, width: u32, height: u32) -> Self {
+        let mut counts: HashMap<[u8; 3], usize> = HashMap::new();
+        for y in y0..y0 + height {
+            for x in x0..x0 + width {
+                *counts.entry(image.pixel(x, y)).or_default() += 1;
+            }
+        }
+        let background = counts.into_iter().max_by_key(|&(colour, count)| (count, colour)).map(|(colour, _)| colour).unwrap_or_default();
+        let distances: Vec<u32> = (y0..y0 + height)
+            .flat_map(|y| (x0..x0 + width).map(move |x| (x, y)))
+            .map(|(x, y)| colour_distance(image.pixel(x, y), background))
+            .collect();
+        // The edges of the letter are blended with the background, so a pixel
+        // is part of it if it is nearer the letter's colour, which contrasts
+        // less with some backgrounds than others.
+        let letter_distance = distances.iter().copied().max().unwrap_or(0);
+        let ink = distances.iter().map(|&distance| letter_distance >= INK_THRESHOLD && distance * 2 >= letter_distance).collect();
+        Tile { background, mask: Mask { width, height, ink } }
+    }
+
+    fn is_blank(&self) -> bool {
+        self.mask.n_ink() * 100 < self.mask.ink.len()
+    }
+
+    fn result_char(&self) -> char {
+        if colour_distance(self.background, EXACT_COLOUR) <= COLOUR_TOLERANCE {
+            'O'
+        } else if colour_distance(self.background, PARTIAL_COLOUR) <= COLOUR_TOLERANCE {
+            'o'
+        } else {
+            ' '
+        }
+    }
+}
+
+/// The shape of each letter as drawn in the game.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Glyphs {
+    templates: Vec<(char, Mask)>,
+}
+
+impl Glyphs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learns the letters from a screenshot whose guesses are known, reading
+    /// every board in `layout`.
+    ///
+    /// The edges of a letter are blended with the colour of its tile, so the
+    /// same letter can look different on each board. Every different shape is
+    /// kept, along with those already known.
+    pub fn capture(&mut self, image: &Image, layout: &GridLayout, guess_history: &[Word]) -> Result<(), ScreenshotError> {
+        if layout.columns.is_empty() {
+            return Err(ScreenshotError::NoColumns)
+        }
+        layout.check()?;
+        for (column, &(x0, y0)) in layout.columns.iter().enumerate() {
+            for (row, guess) in guess_history.iter().enumerate() {
+                for (i, &letter) in guess.bytes().iter().enumerate() {
+                    let tile = layout.tile(image, x0, y0, column, row, i)?;
+                    if !self.templates.iter().any(|(known, mask)| *known == letter as char && *mask == tile.mask) {
+                        self.templates.push((letter as char, tile.mask));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn recognise(&self, mask: &Mask) -> Option<char> {
+        self.templates.iter()
+            .map(|(letter, template)| (*letter, mask.overlap(template)))
+            .filter(|&(_, overlap)| overlap >= MIN_GLYPH_MATCH)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(letter, _)| letter)
+    }
+}
+
+impl GridLayout {
+    pub fn new(columns: Vec<(u32, u32)>, tile_width: u32, tile_height: u32, tile_pitch_x: u32, tile_pitch_y: u32) -> Result<Self, ScreenshotError> {
+        let layout = GridLayout { columns, tile_width, tile_height, tile_pitch_x, tile_pitch_y };
+        layout.check()?;
+        Ok(layout)
+    }
+
+    /// Checks that the tiles have some size and do not overlap, without which
+    /// reading a board would never reach its end.
+    fn check(&self) -> Result<(), ScreenshotError> {
+        if self.tile_width == 0 || self.tile_height == 0 {
+            return Err(ScreenshotError::InvalidLayout("tiles must be at least one pixel in size"))
+        }
+        if self.tile_pitch_x < self.tile_width || self.tile_pitch_y < self.tile_height {
+            return Err(ScreenshotError::InvalidLayout("tiles must be at least their own size apart"))
+        }
+        Ok(())
+    }
+
+    fn tile(&self, image: &Image, x0: u32, y0: u32, column: usize, row: usize, i: usize) -> Result<Tile, ScreenshotError> {
+        // The layout is measured by hand, so sums which overflow count as
+        // outside the image.
+        let start = |origin: u32, n: usize, pitch: u32, size: u32, limit: u32| {
+            let start = u32::try_from(n).ok()?.checked_mul(pitch)?.checked_add(origin)?;
+            (start.checked_add(size)? <= limit).then_some(start)
+        };
+        let x = start(x0, i, self.tile_pitch_x, self.tile_width, image.width);
+        let y = start(y0, row, self.tile_pitch_y, self.tile_height, image.height);
+        match (x, y) {
+            (Some(x), Some(y)) => Ok(Tile::read(image, x, y, self.tile_width, self.tile_height)),
+            _ => Err(ScreenshotError::TileOutOfBounds { column, row }),
+        }
+    }
+}
+
+/// Why the boards could not be read from a screenshot, with columns, rows and
+/// tiles counted from zero.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ScreenshotError {
+    Decode(String),
+    NoColumns,
+    InvalidLayout(&'static str),
+    TileOutOfBounds { column: usize, row: usize },
+    UnknownGlyph { column: usize, row: usize, tile: usize },
+}
+
+impl Display for ScreenshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScreenshotError::Decode(err) => write!(f, "Failed to decode screenshot: {}", err),
+            ScreenshotError::NoColumns => write!(f, "Layout does not have any columns"),
+            ScreenshotError::InvalidLayout(reason) => write!(f, "Invalid layout: {}", reason),
+            ScreenshotError::TileOutOfBounds { column, row } =>
+                write!(f, "Column {} row {} is outside the screenshot before an empty row was found", column, row),
+            ScreenshotError::UnknownGlyph { column, row, tile } =>
+                write!(f, "Column {} row {} tile {} does not match any known letter", column, row, tile),
+        }
+    }
+}
+
+impl Error for ScreenshotError {
+
+}
+
+/// Reads every board in `layout` from a screenshot, up to the first empty row
+/// of each, in the same form as `read_results.js` returns.
+pub fn read_screenshot(image: &Image, layout: &GridLayout, glyphs: &Glyphs) -> Result<Vec<Column>, ScreenshotError> {
+    layout.check()?;
+    layout.columns.iter().enumerate().map(|(column, &(x0, y0))| {
+        let mut guess_history = Vec::new();
+        let mut result_history = Vec::new();
+        for row in 0.. {
+            let tiles = (0..WORD_LENGTH).map(|i| layout.tile(image, x0, y0, column, row, i)).collect::<Result<Vec<_>, _>>()?;
+            if tiles.iter().all(Tile::is_blank) {
+                break
+            }
+            let guess = tiles.iter().enumerate()
+                .map(|(tile, it)| glyphs.recognise(&it.mask).ok_or(ScreenshotError::UnknownGlyph { column, row, tile }))
+                .collect::<Result<String, _>>()?;
+            guess_history.push(guess);
+            result_history.push(tiles.iter().map(Tile::result_char).collect());
+        }
+        Ok(Column { guess_history, result_history })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::GameState;
+    use crate::GuessResult;
+
+    const SCALE: u32 = 4;
+    const TILE: u32 = 32;
+    const PITCH: u32 = 36;
+
+    /// A 5x7 font standing in for the game's, as the letters are all that
+    /// matters to the matching.
+    fn glyph(letter: u8) -> [&'static str; 7] {
+        match letter {
+            b'a' => [" ### ", "#   #", "#   #", "#####", "#   #", "#   #", "#   #"],
+            b'c' => [" ####", "#    ", "#    ", "#    ", "#    ", "#    ", " ####"],
+            b'e' => ["#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#####"],
+            b'i' => [" ### ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### "],
+            b'l' => ["#    ", "#    ", "#    ", "#    ", "#    ", "#    ", "#####"],
+            b'n' => ["#   #", "##  #", "# # #", "#  ##", "#   #", "#   #", "#   #"],
+            b'o' => [" ### ", "#   #", "#   #", "#   #", "#   #", "#   #", " ### "],
+            b'r' => ["#### ", "#   #", "#   #", "#### ", "# #  ", "#  # ", "#   #"],
+            b's' => [" ####", "#    ", "#    ", " ### ", "    #", "    #", "#### "],
+            b't' => ["#####", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  "],
+            _ => panic!("No glyph for {}", letter as char),
+        }
+    }
+
+    /// Draws boards with the given guesses and an empty row after them, and
+    /// round trips the drawing through PNG.
+    fn screenshot(guess_history: &[&str], answers: &[&str]) -> (Image, GridLayout) {
+        let columns: Vec<(u32, u32)> = (0..answers.len() as u32).map(|i| (8 + i * 200, 8)).collect();
+        let width = 8 + answers.len() as u32 * 200;
+        let height = 16 + (guess_history.len() as u32 + 1) * PITCH;
+        let mut pixels = vec![[255, 255, 255]; (width * height) as usize];
+        for (&(x0, y0), answer) in columns.iter().zip(answers) {
+            for (row, guess) in guess_history.iter().enumerate() {
+                let result = GuessResult::evaluate(Word::try_from(*guess).unwrap(), Word::try_from(*answer).unwrap()).to_string();
+                for (i, (letter, result)) in guess.bytes().zip(result.chars()).enumerate() {
+                    let (x, y) = (x0 + i as u32 * PITCH, y0 + row as u32 * PITCH);
+                    let background = match result { 'O' => EXACT_COLOUR, 'o' => PARTIAL_COLOUR, _ => [230, 230, 230] };
+                    for dy in 0..TILE {
+                        for dx in 0..TILE {
+                            let (gx, gy) = (dx.wrapping_sub(6) / SCALE, dy.wrapping_sub(2) / SCALE);
+                            let ink = gx < 5 && gy < 7 && glyph(letter)[gy as usize].as_bytes()[gx as usize] == b'#';
+                            pixels[((y + dy) * width + x + dx) as usize] = if ink { [0, 0, 0] } else { background };
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header().unwrap().write_image_data(&pixels.concat()).unwrap();
+        let layout = GridLayout { columns, tile_width: TILE, tile_height: TILE, tile_pitch_x: PITCH, tile_pitch_y: PITCH };
+        (Image::from_png(png.as_slice()).unwrap(), layout)
+    }
+
+    #[test]
+    fn test_read_screenshot() {
+        let (known, layout) = screenshot(&["soare", "clint"], &["cigar"]);
+        let mut glyphs = Glyphs::new();
+        glyphs.capture(&known, &layout, &[Word::try_from("soare").unwrap(), Word::try_from("clint").unwrap()]).unwrap();
+
+        let (image, layout) = screenshot(&["clint", "soare", "snore"], &["cloth", "stone"]);
+        let columns = read_screenshot(&image, &layout, &glyphs).unwrap();
+        let guess_history = ["clint", "soare", "snore"].map(|guess| Word::try_from(guess).unwrap());
+        let expected: Vec<Column> = ["cloth", "stone"].iter().map(|answer| Column {
+            guess_history: guess_history.iter().map(Word::to_string).collect(),
+            result_history: guess_history.iter().map(|&guess| GuessResult::evaluate(guess, Word::try_from(*answer).unwrap()).to_string()).collect(),
+        }).collect();
+        assert_eq!(columns, expected);
+        assert_eq!(GameState::from_columns(&columns).unwrap().guess_history, guess_history);
+    }
+
+    #[test]
+    fn test_unknown_glyphs_and_bounds() {
+        let (image, layout) = screenshot(&["soare"], &["cigar"]);
+        let mut glyphs = Glyphs::new();
+        glyphs.capture(&image, &layout, &[Word::try_from("soare").unwrap()]).unwrap();
+        let (other, other_layout) = screenshot(&["clint"], &["cigar"]);
+        assert_eq!(read_screenshot(&other, &other_layout, &glyphs), Err(ScreenshotError::UnknownGlyph { column: 0, row: 0, tile: 0 }));
+
+        // Without the empty row, the board runs off the bottom.
+        let height = image.height - PITCH;
+        let cropped = Image { width: image.width, height, pixels: image.pixels[..(image.width * height) as usize].to_vec() };
+        assert_eq!(read_screenshot(&cropped, &layout, &glyphs), Err(ScreenshotError::TileOutOfBounds { column: 0, row: 1 }));
+        assert!(matches!(Image::from_png(&b"not a png"[..]), Err(ScreenshotError::Decode(_))));
+
+        let far_away = GridLayout { columns: vec![(u32::MAX - 8, 8)], ..layout };
+        assert_eq!(read_screenshot(&image, &far_away, &glyphs), Err(ScreenshotError::TileOutOfBounds { column: 0, row: 0 }));
+    }
+
+    #[test]
+    fn test_invalid_layout() {
+        let (image, layout) = screenshot(&["soare"], &["cigar"]);
+        let mut glyphs = Glyphs::new();
+        glyphs.capture(&image, &layout, &[Word::try_from("soare").unwrap()]).unwrap();
+
+        let no_pitch = GridLayout { tile_pitch_y: 0, ..layout.clone() };
+        assert!(matches!(read_screenshot(&image, &no_pitch, &glyphs), Err(ScreenshotError::InvalidLayout(_))));
+        let overlapping = GridLayout { tile_pitch_x: TILE - 1, ..layout.clone() };
+        assert!(matches!(read_screenshot(&image, &overlapping, &glyphs), Err(ScreenshotError::InvalidLayout(_))));
+        let empty_tiles = GridLayout { tile_width: 0, tile_height: 0, ..layout.clone() };
+        assert!(matches!(glyphs.capture(&image, &empty_tiles, &[Word::try_from("soare").unwrap()]), Err(ScreenshotError::InvalidLayout(_))));
+        assert!(matches!(GridLayout::new(layout.columns.clone(), TILE, 0, PITCH, PITCH), Err(ScreenshotError::InvalidLayout(_))));
+        assert_eq!(GridLayout::new(layout.columns.clone(), TILE, TILE, PITCH, PITCH), Ok(layout));
+    }
+
+    /// The layout of the fixtures, which are drawn as the game draws its
+    /// boards, with DejaVu Sans letters blended into the tiles at their edges.
+    fn fixture_layout(n_columns: u32) -> GridLayout {
+        GridLayout::new((0..n_columns).map(|i| (6 + i * 180, 5)).collect(), 30, 30, 33, 33).unwrap()
+    }
+
+    #[test]
+    fn test_read_fixture() {
+        let known = Image::from_png(&include_bytes!("../tests/fixtures/kilordle_grid_glyphs.png")[..]).unwrap();
+        let mut glyphs = Glyphs::new();
+        let known_guesses = ["soare", "clint", "pudgy", "maneh", "brock", "feuar", "wield", "thrip"].map(|guess| Word::try_from(guess).unwrap());
+        glyphs.capture(&known, &fixture_layout(2), &known_guesses).unwrap();
+
+        let image = Image::from_png(&include_bytes!("../tests/fixtures/kilordle_grid.png")[..]).unwrap();
+        let columns = read_screenshot(&image, &fixture_layout(3), &glyphs).unwrap();
+        let guess_history = ["tough", "blimp", "windy"].map(|guess| Word::try_from(guess).unwrap());
+        let expected: Vec<Column> = ["ghoul", "nymph", "timid"].iter().map(|answer| Column {
+            guess_history: guess_history.iter().map(Word::to_string).collect(),
+            result_history: guess_history.iter().map(|&guess| GuessResult::evaluate(guess, Word::try_from(*answer).unwrap()).to_string()).collect(),
+        }).collect();
+        assert_eq!(columns, expected);
+    }
+}
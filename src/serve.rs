@@ -0,0 +1,368 @@
+//! A small local HTTP server for the solver, so that tools which cannot load
+//! the Python extension can still ask it for guesses.
+//!
+//! `POST /suggest` takes the state of a game as JSON and returns the next
+//! guess, `GET /health` reports that the server is up and `GET /metrics`
+//! reports counts of the requests handled. Each connection handles a single
+//! request, on a thread of its own so that the server still answers while a
+//! search is running.
+//!
+//! Any origin may make requests, so that browser extensions and userscripts
+//! can call the server from the game's page.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::{board, solve, GuessResult, Parallelism, SearchLimits, Word};
+
+/// The largest request body accepted.
+const MAX_BODY_BYTES: usize = 1 << 20;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// Sent with every response. The last allows pages on the internet, like the
+/// game's, to call a server on the local machine in browsers which ask first.
+const CORS_HEADERS: &str = "Access-Control-Allow-Origin: *\r\n\
+    Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+    Access-Control-Allow-Headers: Content-Type\r\n\
+    Access-Control-Allow-Private-Network: true\r\n";
+
+/// The body of `POST /suggest`.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+pub struct SuggestRequest {
+    pub guess_history: Vec<Word>,
+    /// The results on each board on screen for the guesses made.
+    pub result_histories: Vec<Vec<GuessResult>>,
+    pub n_remaining_words: usize,
+    #[serde(default)]
+    pub solved_words: Vec<Word>,
+    /// Compare this many of the best guesses by looking two turns ahead.
+    #[serde(default)]
+    pub lookahead: Option<usize>,
+    /// Return the best guess found within this many seconds.
+    #[serde(default)]
+    pub time_limit_seconds: Option<f64>,
+}
+
+/// The reply to `POST /suggest`.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct SuggestResponse {
+    pub guess: Word,
+    /// How many words could still be the answer on each board on screen.
+    pub possible_words_per_board: Vec<usize>,
+    pub solve_seconds: f64,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Counts of the requests handled, reported by `GET /metrics`.
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    started: Instant,
+    requests: u64,
+    suggestions: u64,
+    errors: u64,
+    total_solve_seconds: f64,
+}
+
+#[derive(Serialize)]
+struct MetricsResponse {
+    uptime_seconds: f64,
+    requests: u64,
+    suggestions: u64,
+    errors: u64,
+    total_solve_seconds: f64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics { started: Instant::now(), requests: 0, suggestions: 0, errors: 0, total_solve_seconds: 0.0 }
+    }
+
+    fn response(&self) -> MetricsResponse {
+        MetricsResponse {
+            uptime_seconds: self.started.elapsed().as_secs_f64(),
+            requests: self.requests,
+            suggestions: self.suggestions,
+            errors: self.errors,
+            total_solve_seconds: self.total_solve_seconds,
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reply to a request, which is JSON unless it has no body.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: &impl Serialize) -> Self {
+        let body = serde_json::to_string(body).expect("Responses can always be written as JSON");
+        Response { status, body }
+    }
+
+    fn error(status: u16, error: impl ToString) -> Self {
+        Response::json(status, &ErrorResponse { error: error.to_string() })
+    }
+
+    fn no_content() -> Self {
+        Response { status: 204, body: String::new() }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        _ => "Error",
+    }
+}
+
+/// The metrics are only locked to update them, and not during a search.
+fn lock(metrics: &Mutex<Metrics>) -> MutexGuard<'_, Metrics> {
+    metrics.lock().expect("Lock should not be poisoned")
+}
+
+/// Answers a single request, searching for guesses with `parallelism`.
+pub fn handle(method: &str, path: &str, body: &[u8], parallelism: &Parallelism, metrics: &Mutex<Metrics>) -> Response {
+    lock(metrics).requests += 1;
+    let response = match (method, path) {
+        ("GET", "/health") => Response::json(200, &serde_json::json!({ "status": "ok" })),
+        ("GET", "/metrics") => Response::json(200, &lock(metrics).response()),
+        ("POST", "/suggest") => suggest(body, parallelism, metrics),
+        // The preflight a browser sends before a cross-origin request with a JSON body.
+        ("OPTIONS", "/health" | "/metrics" | "/suggest") => Response::no_content(),
+        (_, "/health" | "/metrics" | "/suggest") => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, "Not found"),
+    };
+    if response.status >= 400 {
+        lock(metrics).errors += 1;
+    }
+    response
+}
+
+/// Counts a request turned away before it could be handled.
+fn reject(status: u16, error: &str, metrics: &Mutex<Metrics>) -> Response {
+    let mut metrics = lock(metrics);
+    metrics.requests += 1;
+    metrics.errors += 1;
+    Response::error(status, error)
+}
+
+fn suggest(body: &[u8], parallelism: &Parallelism, metrics: &Mutex<Metrics>) -> Response {
+    let request: SuggestRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(err) => return Response::error(400, format!("Invalid request: {}", err)),
+    };
+    let started = Instant::now();
+    let limits = SearchLimits {
+        deadline: request.time_limit_seconds.map(|seconds| started + Duration::from_secs_f64(seconds.clamp(0.0, 1e9))),
+        cancelled: None,
+    };
+    let guess = parallelism.install(|| {
+        solve(&request.guess_history, &request.result_histories, request.n_remaining_words, &request.solved_words, request.lookahead, &limits)
+    });
+    let guess = match guess {
+        Ok(guess) => guess,
+        Err(err) => return Response::error(400, err),
+    };
+    let possible_words_per_board = match request.result_histories.iter()
+        .map(|results| board::count_possible_words(&request.guess_history, results, &request.solved_words))
+        .collect::<Result<_, _>>() {
+        Ok(counts) => counts,
+        // The solver has already checked the histories, so this should not happen.
+        Err(err) => return Response::error(500, format!("Failed to count possible words: {}", err)),
+    };
+    let solve_seconds = started.elapsed().as_secs_f64();
+    let mut metrics = lock(metrics);
+    metrics.suggestions += 1;
+    metrics.total_solve_seconds += solve_seconds;
+    Response::json(200, &SuggestResponse { guess, possible_words_per_board, solve_seconds })
+}
+
+/// Reads a request from `stream` and writes back the response.
+pub fn handle_connection(stream: &mut TcpStream, parallelism: &Parallelism, metrics: &Mutex<Metrics>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&*stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or("").to_string(), parts.next().unwrap_or("").to_string());
+
+    // `None` if the header could not be read.
+    let mut content_length = Some(0);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok();
+            }
+        }
+    }
+
+    let response = match content_length {
+        None => reject(400, "Invalid Content-Length header", metrics),
+        Some(content_length) if content_length > MAX_BODY_BYTES => reject(413, "Request body is too large", metrics),
+        Some(content_length) => {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            handle(&method, &path, &body, parallelism, metrics)
+        },
+    };
+
+    write!(stream, "HTTP/1.1 {} {}\r\n", response.status, reason_phrase(response.status))?;
+    if !response.body.is_empty() {
+        write!(stream, "Content-Type: application/json\r\n")?;
+    }
+    write!(
+        stream,
+        "Content-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        response.body.len(), CORS_HEADERS, response.body,
+    )?;
+    stream.flush()
+}
+
+/// Answers requests on `listener`, each on a new thread, searching for guesses
+/// with `parallelism`.
+pub fn serve(listener: TcpListener, parallelism: &Parallelism) -> std::io::Result<()> {
+    let metrics = Arc::new(Mutex::new(Metrics::new()));
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("Failed to accept connection: {}", err);
+                continue
+            },
+        };
+        let (parallelism, metrics) = (parallelism.clone(), metrics.clone());
+        std::thread::spawn(move || {
+            if let Err(err) = handle_connection(&mut stream, &parallelism, &metrics) {
+                eprintln!("Failed to handle request: {}", err);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_and_unknown_paths() {
+        let metrics = Mutex::new(Metrics::new());
+        assert_eq!(handle("GET", "/health", b"", &Parallelism::global(), &metrics), Response { status: 200, body: "{\"status\":\"ok\"}".to_string() });
+        assert_eq!(handle("GET", "/nothing", b"", &Parallelism::global(), &metrics).status, 404);
+        assert_eq!(handle("GET", "/suggest", b"", &Parallelism::global(), &metrics).status, 405);
+        assert_eq!(handle("OPTIONS", "/suggest", b"", &Parallelism::global(), &metrics), Response { status: 204, body: String::new() });
+        let metrics = metrics.into_inner().unwrap();
+        assert_eq!((metrics.requests, metrics.errors), (4, 2));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let metrics = Mutex::new(Metrics::new());
+        let body = br#"{"guess_history": [], "result_histories": [[], []], "n_remaining_words": 1000}"#;
+        let response = handle("POST", "/suggest", body, &Parallelism::global(), &metrics);
+        assert_eq!(response.status, 200);
+        let response: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(response["guess"], "soare");
+        let n_wordles = crate::dict::wordles().count();
+        assert_eq!(response["possible_words_per_board"], serde_json::json!([n_wordles, n_wordles]));
+        assert_eq!(lock(&metrics).suggestions, 1);
+    }
+
+    #[test]
+    fn test_suggest_invalid_requests() {
+        let metrics = Mutex::new(Metrics::new());
+        assert_eq!(handle("POST", "/suggest", b"{", &Parallelism::global(), &metrics).status, 400);
+        let bad_word = br#"{"guess_history": ["SOARE"], "result_histories": [], "n_remaining_words": 1000}"#;
+        assert_eq!(handle("POST", "/suggest", bad_word, &Parallelism::global(), &metrics).status, 400);
+        let mismatch = br#"{"guess_history": ["soare"], "result_histories": [[]], "n_remaining_words": 1000}"#;
+        assert_eq!(handle("POST", "/suggest", mismatch, &Parallelism::global(), &metrics).status, 400);
+        assert_eq!(lock(&metrics).suggestions, 0);
+    }
+
+    fn start_server() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || serve(listener, &Parallelism::sequential().unwrap()));
+        address
+    }
+
+    fn send_request(address: std::net::SocketAddr, request: &str) -> TcpStream {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        stream
+    }
+
+    fn request_over_tcp(request: &str) -> String {
+        let mut response = String::new();
+        send_request(start_server(), request).read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_over_tcp() {
+        let response = request_over_tcp("GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\r\nAccess-Control-Allow-Origin: *\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"status\":\"ok\"}"));
+    }
+
+    #[test]
+    fn test_preflight() {
+        let response = request_over_tcp(
+            "OPTIONS /suggest HTTP/1.1\r\nHost: localhost\r\nOrigin: https://jonesnxt.github.io\r\n\
+            Access-Control-Request-Method: POST\r\nAccess-Control-Request-Headers: content-type\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 204 No Content\r\n"));
+        assert!(response.contains("\r\nAccess-Control-Allow-Origin: *\r\n"));
+        assert!(response.contains("\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\n"));
+        assert!(response.contains("\r\nAccess-Control-Allow-Headers: Content-Type\r\n"));
+        assert!(response.ends_with("\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_invalid_content_length() {
+        let response = request_over_tcp("POST /suggest HTTP/1.1\r\nHost: localhost\r\nContent-Length: ten\r\n\r\n{}");
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(response.ends_with("{\"error\":\"Invalid Content-Length header\"}"));
+    }
+
+    #[test]
+    fn test_health_during_search() {
+        let address = start_server();
+        // Looking ahead from the start of a game takes far longer than this test.
+        let body = r#"{"guess_history": [], "result_histories": [[]], "n_remaining_words": 999, "lookahead": 8, "time_limit_seconds": 5}"#;
+        let mut searching = send_request(address, &format!("POST /suggest HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body));
+
+        let mut response = String::new();
+        send_request(address, "GET /health HTTP/1.1\r\n\r\n").read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+
+        searching.set_nonblocking(true).unwrap();
+        let still_searching = searching.read(&mut [0; 1]).unwrap_err();
+        assert_eq!(still_searching.kind(), std::io::ErrorKind::WouldBlock);
+    }
+}
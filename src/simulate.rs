@@ -0,0 +1,208 @@
+//! Offline simulation of kilordle games, for trying out the solver without a browser.
+
+use crate::{dict, search_next_guess, GuessResult, PickGuessError, ScoringParams, ScoringState, SearchLimits, Word};
+
+/// Roughly how many boards the game fits on a screen.
+pub const DEFAULT_VISIBLE_BOARDS: usize = 10;
+
+/// A small deterministic random number generator (splitmix64), so the same
+/// seed always gives the same games.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize
+    }
+
+    fn uniform(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    fn log_uniform(&mut self, low: f64, high: f64) -> f64 {
+        self.uniform(low.ln(), high.ln()).exp()
+    }
+}
+
+/// A game of kilordle with known hidden words.
+///
+/// Only the unsolved boards with the most progress are shown to the solver,
+/// like the boards which fit on screen in the real game.
+#[derive(Clone, Debug)]
+pub struct SimulatedGame {
+    secrets: Vec<Word>,
+    solved: Vec<bool>,
+    states: Vec<ScoringState>,
+    result_histories: Vec<Vec<GuessResult>>,
+    guess_history: Vec<Word>,
+    n_visible_boards: usize,
+}
+
+impl SimulatedGame {
+    pub fn new(secrets: Vec<Word>, n_visible_boards: usize) -> Self {
+        SimulatedGame {
+            solved: vec![false; secrets.len()],
+            states: secrets.iter().map(|&word| ScoringState::for_word(word)).collect(),
+            result_histories: vec![Vec::new(); secrets.len()],
+            secrets,
+            guess_history: Vec::new(),
+            n_visible_boards,
+        }
+    }
+
+    /// A game with `n_words` distinct hidden words picked from the wordles.
+    pub fn random(rng: &mut Rng, n_words: usize, n_visible_boards: usize) -> Self {
+        let mut wordles: Vec<Word> = dict::wordles().collect();
+        let n_words = n_words.min(wordles.len());
+        for i in 0..n_words {
+            let j = i + rng.below(wordles.len() - i);
+            wordles.swap(i, j);
+        }
+        wordles.truncate(n_words);
+        SimulatedGame::new(wordles, n_visible_boards)
+    }
+
+    pub fn guess_history(&self) -> &[Word] {
+        &self.guess_history
+    }
+
+    pub fn n_remaining_words(&self) -> usize {
+        self.solved.iter().filter(|&&solved| !solved).count()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.solved.iter().all(|&solved| solved)
+    }
+
+    /// The result histories of the boards on screen.
+    pub fn visible_results(&self) -> Vec<Vec<GuessResult>> {
+        let mut unsolved: Vec<usize> = (0..self.secrets.len()).filter(|&i| !self.solved[i]).collect();
+        unsolved.sort_by_key(|&i| std::cmp::Reverse(self.states[i].current_score()));
+        unsolved.truncate(self.n_visible_boards);
+        unsolved.into_iter().map(|i| self.result_histories[i].clone()).collect()
+    }
+
+    pub fn make_guess(&mut self, guess: Word) {
+        self.guess_history.push(guess);
+        for (i, &secret) in self.secrets.iter().enumerate() {
+            self.states[i].add_history_item(guess);
+            self.result_histories[i].push(GuessResult::evaluate(guess, secret));
+            if secret == guess {
+                self.solved[i] = true;
+            }
+        }
+    }
+}
+
+/// Plays `game` to the end with the solver, returning the number of guesses
+/// taken or `None` if it was not finished within `max_guesses`.
+///
+/// The opening book is not used, since it only has moves for the default
+/// parameters and would make them look better than they are when tuning.
+pub fn play_game(game: &mut SimulatedGame, params: &ScoringParams, max_guesses: usize) -> Result<Option<usize>, PickGuessError> {
+    while !game.is_finished() {
+        if game.guess_history().len() >= max_guesses {
+            return Ok(None)
+        }
+        let visible_results = game.visible_results();
+        let guess = search_next_guess(game.guess_history(), &visible_results, game.n_remaining_words(), &[], params, &SearchLimits::default())?;
+        game.make_guess(guess);
+    }
+    Ok(Some(game.guess_history().len()))
+}
+
+/// Picks scoring parameters at random from a range around the defaults.
+pub fn sample_params(rng: &mut Rng) -> ScoringParams {
+    ScoringParams {
+        exact_points: rng.uniform(1.0, 6.0),
+        partial_points: rng.uniform(0.0, 3.0),
+        invisible_bonus_log_base: rng.log_uniform(2.0, 50.0),
+        visible_weight: rng.log_uniform(0.25, 4.0),
+        invisible_weight: rng.log_uniform(0.25, 4.0),
+    }
+}
+
+/// The mean of some samples with a 95% confidence interval.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Summary {
+    pub n: usize,
+    pub mean: f64,
+    /// Half the width of the confidence interval, from the normal approximation.
+    pub half_width: f64,
+}
+
+impl Summary {
+    pub fn of(samples: &[f64]) -> Self {
+        let n = samples.len();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let half_width = if n < 2 {
+            f64::INFINITY
+        } else {
+            let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1) as f64;
+            1.96 * (variance / n as f64).sqrt()
+        };
+        Summary { n, mean, half_width }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(s: &str) -> Word {
+        s.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_game_finishes_when_all_words_guessed() {
+        let mut game = SimulatedGame::new(vec![word("hello"), word("world")], 1);
+        assert_eq!(game.visible_results().len(), 1);
+        game.make_guess(word("world"));
+        assert_eq!(game.n_remaining_words(), 1);
+        assert!(!game.is_finished());
+        game.make_guess(word("hello"));
+        assert!(game.is_finished());
+        assert!(game.visible_results().is_empty());
+    }
+
+    #[test]
+    fn test_visible_boards_are_most_progressed() {
+        let mut game = SimulatedGame::new(vec![word("crane"), word("hello")], 1);
+        game.make_guess(word("hells"));
+        let visible = game.visible_results();
+        assert_eq!(visible, vec![vec![GuessResult::evaluate(word("hells"), word("hello"))]]);
+    }
+
+    #[test]
+    fn test_play_small_game_to_the_end() {
+        let mut game = SimulatedGame::random(&mut Rng::new(1), 8, DEFAULT_VISIBLE_BOARDS);
+        let n_guesses = play_game(&mut game, &ScoringParams::default(), 40).unwrap();
+        assert!(game.is_finished());
+        assert_eq!(n_guesses, Some(game.guess_history().len()));
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::of(&[1.0, 2.0, 3.0]);
+        assert_eq!(summary.mean, 2.0);
+        assert!((summary.half_width - 1.96 / 3f64.sqrt()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,73 @@
+//! The WebAssembly module, so that the solver can run in the page itself.
+//!
+//! Words and results are passed as strings, and lists of them as arrays, in
+//! the same formats as from Python. Without threads, the searches run on the
+//! calling thread.
+
+use wasm_bindgen::prelude::*;
+use crate::{board, solve, GuessResult, SearchLimits, Word};
+
+fn word(word: &str) -> Result<Word, JsError> {
+    Ok(Word::try_from(word)?)
+}
+
+/// Finds a next guess, from the guesses made so far, the results on each board
+/// on screen and the number of words left to find.
+///
+/// `solved_words` may be left out, and `lookahead` compares that many of the
+/// best guesses by looking two turns ahead.
+#[wasm_bindgen]
+pub fn pick_next_guess(guess_history: JsValue, result_histories: JsValue, n_remaining_words: usize, solved_words: JsValue, lookahead: Option<usize>) -> Result<String, JsError> {
+    let guess_history: Vec<Word> = serde_wasm_bindgen::from_value(guess_history)?;
+    let result_histories: Vec<Vec<GuessResult>> = serde_wasm_bindgen::from_value(result_histories)?;
+    let solved_words: Option<Vec<Word>> = serde_wasm_bindgen::from_value(solved_words)?;
+    // Deadlines are left out, as there is no clock to check them against.
+    let guess = solve(&guess_history, &result_histories, n_remaining_words, &solved_words.unwrap_or_default(), lookahead, &SearchLimits::default())?;
+    Ok(guess.to_string())
+}
+
+/// Lists the wordles which could still be the answer on a board, leaving out
+/// guessed words and `solved_words`, which may be left out.
+#[wasm_bindgen]
+pub fn possible_words(guess_history: JsValue, result_history: JsValue, solved_words: JsValue) -> Result<JsValue, JsError> {
+    let guess_history: Vec<Word> = serde_wasm_bindgen::from_value(guess_history)?;
+    let result_history: Vec<GuessResult> = serde_wasm_bindgen::from_value(result_history)?;
+    let solved_words: Option<Vec<Word>> = serde_wasm_bindgen::from_value(solved_words)?;
+    let words = board::possible_words(&guess_history, &result_history, &solved_words.unwrap_or_default())?;
+    Ok(serde_wasm_bindgen::to_value(&words)?)
+}
+
+/// The result shown for `guess` on the board whose answer is `answer`.
+#[wasm_bindgen]
+pub fn evaluate(guess: &str, answer: &str) -> Result<String, JsError> {
+    Ok(GuessResult::evaluate(word(guess)?, word(answer)?).to_string())
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn to_value<T: serde::Serialize>(value: &T) -> JsValue {
+        serde_wasm_bindgen::to_value(value).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_evaluate() {
+        assert_eq!(evaluate("soare", "cigar").unwrap(), "  oo ");
+        assert!(evaluate("soar", "cigar").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_pick_next_guess() {
+        let guess = pick_next_guess(to_value(&["soare"]), to_value(&[["  o o"]]), 1000, JsValue::UNDEFINED, None).unwrap();
+        assert_eq!(guess.len(), 5);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_possible_words() {
+        let words = possible_words(to_value(&["soare"]), to_value(&["  oo "]), JsValue::UNDEFINED).unwrap();
+        let words: Vec<String> = serde_wasm_bindgen::from_value(words).unwrap();
+        assert!(words.contains(&"cigar".to_string()));
+    }
+}
@@ -1,10 +1,15 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use pyo3::{FromPyObject, PyAny, PyResult};
+use std::str::FromStr;
+#[cfg(feature = "python")]
+use pyo3::{FromPyObject, PyAny, PyCell, PyResult};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
 
 pub const WORD_LENGTH: usize = 5;
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Word([u8; WORD_LENGTH]);
 
 impl From<[u8; 5]> for Word {
@@ -34,6 +39,20 @@ impl TryFrom<&str> for Word {
     }
 }
 
+impl FromStr for Word {
+    type Err = WordOfStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Word::try_from(s)
+    }
+}
+
+impl Display for Word {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(std::str::from_utf8(&self.0).unwrap())
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum WordOfStringError {
     WrongLength,
@@ -53,6 +72,74 @@ impl Error for WordOfStringError {
 
 }
 
+/// Why `Word::parse` could not read a word.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ParseWordError {
+    /// The word had this many letters after tidying up.
+    WrongLength { length: usize },
+    /// The character at `position`, counting characters from zero after
+    /// trimming, is not a letter.
+    InvalidCharacter { position: usize, character: char },
+}
+
+impl Display for ParseWordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseWordError::WrongLength { length } =>
+                write!(f, "Wrong length: word must be exactly {} letters, but has {}", WORD_LENGTH, length),
+            ParseWordError::InvalidCharacter { position, character } =>
+                write!(f, "Invalid character {:?} at position {}: all letters in word must be a..z", character, position),
+        }
+    }
+}
+
+impl Error for ParseWordError {
+
+}
+
+impl Word {
+    /// Reads a word from messy text such as user input or a game page,
+    /// trimming surrounding whitespace and folding upper case to lower case.
+    /// With `strip_accents`, accented letters are read as the letters without
+    /// their accents.
+    pub fn parse(s: &str, strip_accents: bool) -> Result<Self, ParseWordError> {
+        let mut letters = Vec::with_capacity(WORD_LENGTH);
+        for (position, character) in s.trim().chars().enumerate() {
+            let mut invalid = false;
+            let mut read = |c: char| {
+                if strip_accents && is_combining_mark(c) {
+                    return
+                }
+                let c = c.to_ascii_lowercase();
+                if c.is_ascii_lowercase() {
+                    letters.push(c as u8);
+                } else {
+                    invalid = true;
+                }
+            };
+            if strip_accents {
+                decompose_canonical(character, &mut read);
+            } else {
+                read(character);
+            }
+            if invalid {
+                return Err(ParseWordError::InvalidCharacter { position, character })
+            }
+        }
+        let letters: [u8; WORD_LENGTH] = letters.as_slice().try_into()
+            .map_err(|_| ParseWordError::WrongLength { length: letters.len() })?;
+        Ok(Word(letters))
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<ParseWordError> for pyo3::PyErr {
+    fn from(err: ParseWordError) -> Self {
+        pyo3::exceptions::PyValueError::new_err(err.to_string())
+    }
+}
+
+#[cfg(feature = "python")]
 impl WordOfStringError {
     fn into_value_error(&self) -> pyo3::PyErr {
         pyo3::exceptions::PyValueError::new_err(self.to_string())
@@ -88,9 +175,70 @@ impl IntoIterator for Word {
     }
 }
 
+#[cfg(feature = "python")]
 impl<'source> FromPyObject<'source> for Word {
     fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(word) = ob.downcast::<PyCell<crate::py_types::PyWord>>() {
+            return Ok(word.borrow().0)
+        }
         Word::try_from(<&str as FromPyObject>::extract(ob)?)
             .map_err(|err| err.into_value_error())
     }
 }
+
+#[cfg(feature = "serde")]
+impl Serialize for Word {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Word {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let word = String::deserialize(deserializer)?;
+        Word::try_from(word.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(s: &str) -> Word {
+        Word::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let soare = word("soare");
+        assert_eq!(soare.to_string(), "soare");
+        assert_eq!(format!("{:>7}|", soare), "  soare|");
+        assert_eq!("soare".parse::<Word>(), Ok(soare));
+        assert_eq!("Soare".parse::<Word>(), Err(WordOfStringError::InvalidLetter));
+        let words: std::collections::HashSet<Word> = [soare, word("crane"), soare].into_iter().collect();
+        assert_eq!(words.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_tidies_up() {
+        assert_eq!(Word::parse("  Soare\n", false), Ok(word("soare")));
+        assert_eq!(Word::parse("CRANE", false), Ok(word("crane")));
+        assert_eq!(Word::parse("Crème", false), Err(ParseWordError::InvalidCharacter { position: 2, character: 'è' }));
+        assert_eq!(Word::parse("Crème", true), Ok(word("creme")));
+        assert_eq!(Word::parse("cre\u{300}me", true), Ok(word("creme")));
+        assert_eq!(Word::parse("ÉCLAT", true), Ok(word("eclat")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Word::parse("so are", false), Err(ParseWordError::InvalidCharacter { position: 2, character: ' ' }));
+        assert_eq!(Word::parse("soar3", true), Err(ParseWordError::InvalidCharacter { position: 4, character: '3' }));
+        assert_eq!(Word::parse("soares", false), Err(ParseWordError::WrongLength { length: 6 }));
+        assert_eq!(Word::parse("   ", false), Err(ParseWordError::WrongLength { length: 0 }));
+        assert_eq!(
+            Word::parse("ab!de", false).unwrap_err().to_string(),
+            "Invalid character '!' at position 2: all letters in word must be a..z",
+        );
+    }
+}